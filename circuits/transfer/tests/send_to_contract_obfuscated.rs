@@ -140,3 +140,66 @@ fn send_to_contract_obfuscated_private_key() {
     )
     .expect("Failed to verify the proof!");
 }
+
+#[test]
+fn send_to_contract_obfuscated_wrong_value() {
+    let mut rng = StdRng::seed_from_u64(2322u64);
+
+    let ssk = SecretSpendKey::random(&mut rng);
+    let vk = ssk.view_key();
+    let psk = ssk.public_spend_key();
+
+    let c_address = BlsScalar::random(&mut rng);
+
+    let c_value = 100;
+    let c_blinding_factor = JubJubScalar::random(&mut rng);
+    let c_note = Note::obfuscated(&mut rng, &psk, c_value, c_blinding_factor);
+    let (mut fee, crossover) = c_note
+        .try_into()
+        .expect("Failed to convert note into fee/crossover pair!");
+    fee.gas_limit = 5;
+    fee.gas_price = 1;
+
+    // The crossover value commitment doesn't open to the message value.
+    let message_r = JubJubScalar::random(&mut rng);
+    let message_value = 99;
+    let message = Message::new(&mut rng, &message_r, &psk, message_value);
+
+    let c_signature = SendToContractObfuscatedCircuit::sign(
+        &mut rng, &ssk, &fee, &crossover, &message, &c_address,
+    );
+
+    let mut circuit = SendToContractObfuscatedCircuit::new(
+        fee,
+        crossover,
+        &vk,
+        c_signature,
+        true,
+        message,
+        &psk,
+        message_r,
+        c_address,
+    )
+    .expect("Failed to generate circuit!");
+
+    let (pp, pk, vd) = keys::circuit_keys::<SendToContractObfuscatedCircuit>()
+        .expect("Failed to generate circuit!");
+
+    // Either the prover refuses the unsatisfied circuit outright, or its
+    // proof doesn't verify.
+    let proof = match circuit.gen_proof(&pp, &pk, TRANSCRIPT_LABEL) {
+        Ok(proof) => proof,
+        Err(_) => return,
+    };
+    let pi = circuit.public_inputs();
+
+    assert!(circuit::verify_proof(
+        &pp,
+        vd.key(),
+        &proof,
+        pi.as_slice(),
+        vd.pi_pos(),
+        TRANSCRIPT_LABEL,
+    )
+    .is_err());
+}
@@ -0,0 +1,14 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use phoenix_core::Fee;
+
+fuzz_target!(|bytes: &[u8]| {
+    transfer_contract_fuzz::decode::<Fee>(bytes);
+});
@@ -0,0 +1,39 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Decoding of the values the transfer contract reads from its callers.
+
+use canonical::{ByteSink, ByteSource, Canon};
+use canonical_host::MemStore;
+
+/// Decode a `T` from arbitrary `bytes`, as `bridge.rs` reads its calls.
+///
+/// Malformed bytes must be refused with an error. Whatever decodes encodes
+/// back to bytes that decode to the very same encoding.
+pub fn decode<T: Canon<MemStore>>(bytes: &[u8]) {
+    let store = MemStore::new();
+
+    let mut source = ByteSource::new(bytes, &store);
+    let value: T = match Canon::read(&mut source) {
+        Ok(value) => value,
+        Err(_) => return,
+    };
+
+    let encoded = encode(&value, &store);
+    let mut source = ByteSource::new(&encoded[..], &store);
+    let decoded: T = Canon::read(&mut source)
+        .expect("Failed to decode a value the contract encoded!");
+
+    assert_eq!(encode(&decoded, &store), encoded);
+}
+
+fn encode<T: Canon<MemStore>>(value: &T, store: &MemStore) -> Vec<u8> {
+    let mut bytes = vec![0u8; value.encoded_len()];
+    let mut sink = ByteSink::new(&mut bytes[..], store);
+    Canon::write(value, &mut sink).expect("Failed to encode a value!");
+
+    bytes
+}
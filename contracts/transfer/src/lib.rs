@@ -15,6 +15,9 @@ extern crate alloc;
 #[cfg(target_arch = "wasm32")]
 mod wasm;
 
+mod public_inputs;
+pub use public_inputs::stct_public_inputs;
+
 mod transfer;
 pub use transfer::{Call, TransferContract};
 
@@ -0,0 +1,52 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use alloc::vec::Vec;
+use dusk_bls12_381::BlsScalar;
+use dusk_jubjub::JubJubAffine;
+use dusk_pki::PublicKey;
+use phoenix_core::Crossover;
+
+/// Public inputs the contract verifies the STCT proof of sending `value` of
+/// the `crossover` to the contract at `address` with, `pk` being the key
+/// the crossover was signed with.
+///
+/// They are laid out as the circuit declares them: the coordinates of the
+/// value commitment and of `pk`, the signed message and the value.
+pub fn stct_public_inputs(
+    crossover: &Crossover,
+    pk: &PublicKey,
+    value: u64,
+    address: &BlsScalar,
+) -> Vec<BlsScalar> {
+    let value_commitment = JubJubAffine::from(crossover.value_commitment());
+    let pk = JubJubAffine::from(pk.as_ref());
+
+    let mut message = crossover.to_hash_inputs().to_vec();
+    message.push(value.into());
+    message.push(*address);
+
+    let mut pi = Vec::with_capacity(6);
+
+    pi.push(value_commitment.get_x());
+    pi.push(value_commitment.get_y());
+    pi.push(pk.get_x());
+    pi.push(pk.get_y());
+    pi.push(hash(message));
+    pi.push(value.into());
+
+    pi
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn hash(scalars: Vec<BlsScalar>) -> BlsScalar {
+    dusk_poseidon::sponge::hash(&scalars)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn hash(scalars: Vec<BlsScalar>) -> BlsScalar {
+    rusk_abi::poseidon_hash(scalars)
+}
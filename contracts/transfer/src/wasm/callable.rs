@@ -5,7 +5,7 @@
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
 use super::keys;
-use crate::TransferContract;
+use crate::{stct_public_inputs, TransferContract};
 
 use alloc::vec::Vec;
 use canonical::Store;
@@ -14,6 +14,7 @@ use dusk_bls12_381::BlsScalar;
 use dusk_jubjub::JubJubAffine;
 use dusk_pki::{Ownable, PublicKey};
 use phoenix_core::{Crossover, Fee, Message, Note, NoteType};
+use rusk_abi::PublicInput;
 
 impl<S: Store> TransferContract<S> {
     pub fn send_to_contract_transparent(
@@ -26,11 +27,10 @@ impl<S: Store> TransferContract<S> {
             .take_crossover()
             .expect("The crossover is mandatory for STCT!");
 
-        let mut pi = Vec::with_capacity(3);
-
-        pi.push(crossover.value_commitment().into());
-        pi.push(pk.as_ref().into());
-        pi.push(value.into());
+        let pi = stct_public_inputs(&crossover, &pk, value, &address)
+            .into_iter()
+            .map(PublicInput::from)
+            .collect();
 
         //  1. v < 2^64
         //  2. B_a↦ = B_a↦ + v
@@ -0,0 +1,287 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Round-trips of the values `callable.rs` receives across the contract
+//! boundary, encoded the way `bridge.rs` reads them.
+
+use std::convert::TryInto;
+
+use canonical::{ByteSink, ByteSource, Canon};
+use canonical_host::MemStore;
+use dusk_abi::ContractId;
+use dusk_bls12_381::BlsScalar;
+use dusk_jubjub::{JubJubAffine, JubJubScalar, GENERATOR_EXTENDED};
+use dusk_pki::{PublicKey, SecretKey, SecretSpendKey};
+use phoenix_core::{Crossover, Fee, Message, Note};
+use proptest::collection::vec;
+use proptest::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use transfer_contract::Call;
+
+fn encode<T: Canon<MemStore>>(value: &T, store: &MemStore) -> Vec<u8> {
+    let mut bytes = vec![0u8; value.encoded_len()];
+    let mut sink = ByteSink::new(&mut bytes[..], store);
+    Canon::write(value, &mut sink).unwrap();
+
+    bytes
+}
+
+fn decode<T: Canon<MemStore>>(
+    bytes: &[u8],
+    store: &MemStore,
+) -> Result<T, <MemStore as canonical::Store>::Error> {
+    let mut source = ByteSource::new(bytes, store);
+    Canon::read(&mut source)
+}
+
+// Decode `value` back from its encoding, checking nothing is lost, then feed
+// the decoder all-zero and all-0xff bytes of the same length: either they
+// are refused or their value encodes back to the very same bytes.
+fn assert_round_trip<T: Canon<MemStore>>(value: &T) {
+    let store = MemStore::new();
+
+    let bytes = encode(value, &store);
+    let decoded: T = decode(&bytes, &store).unwrap();
+    assert_eq!(encode(&decoded, &store), bytes);
+
+    for byte in [0x00u8, 0xff].iter() {
+        let bytes = vec![*byte; bytes.len()];
+        if let Ok(decoded) = decode::<T>(&bytes, &store) {
+            assert_eq!(encode(&decoded, &store), bytes);
+        }
+    }
+}
+
+fn fee_crossover(rng: &mut StdRng) -> (Fee, Crossover) {
+    let psk = SecretSpendKey::random(rng).public_spend_key();
+    let blinding_factor = JubJubScalar::random(rng);
+    let note = Note::obfuscated(rng, &psk, 100, blinding_factor);
+
+    let (mut fee, crossover) = note.try_into().unwrap();
+    fee.gas_limit = 50;
+    fee.gas_price = 1;
+
+    (fee, crossover)
+}
+
+// The curve values have no strategy of their own, so they are drawn from
+// a seeded rng with the seed picked by proptest.
+fn scalar() -> impl Strategy<Value = BlsScalar> {
+    any::<u64>()
+        .prop_map(|seed| BlsScalar::random(&mut StdRng::seed_from_u64(seed)))
+}
+
+fn point() -> impl Strategy<Value = JubJubAffine> {
+    any::<u64>().prop_map(|seed| {
+        let rng = &mut StdRng::seed_from_u64(seed);
+        JubJubAffine::from(GENERATOR_EXTENDED * JubJubScalar::random(rng))
+    })
+}
+
+fn public_key() -> impl Strategy<Value = PublicKey> {
+    any::<u64>().prop_map(|seed| {
+        PublicKey::from(&SecretKey::random(&mut StdRng::seed_from_u64(seed)))
+    })
+}
+
+fn note() -> impl Strategy<Value = Note> {
+    (any::<u64>(), any::<u64>(), any::<bool>()).prop_map(
+        |(seed, value, obfuscated)| {
+            let rng = &mut StdRng::seed_from_u64(seed);
+            let psk = SecretSpendKey::random(rng).public_spend_key();
+
+            if obfuscated {
+                let blinding_factor = JubJubScalar::random(rng);
+                Note::obfuscated(rng, &psk, value, blinding_factor)
+            } else {
+                Note::transparent(rng, &psk, value)
+            }
+        },
+    )
+}
+
+fn message() -> impl Strategy<Value = Message> {
+    (any::<u64>(), any::<u64>()).prop_map(|(seed, value)| {
+        let rng = &mut StdRng::seed_from_u64(seed);
+        let psk = SecretSpendKey::random(rng).public_spend_key();
+        let r = JubJubScalar::random(rng);
+
+        Message::new(rng, &r, &psk, value)
+    })
+}
+
+fn fee_and_crossover() -> impl Strategy<Value = (Fee, Crossover)> {
+    (any::<u64>(), any::<u64>(), any::<u64>(), any::<u64>()).prop_map(
+        |(seed, value, gas_limit, gas_price)| {
+            let rng = &mut StdRng::seed_from_u64(seed);
+            let psk = SecretSpendKey::random(rng).public_spend_key();
+            let blinding_factor = JubJubScalar::random(rng);
+            let note = Note::obfuscated(rng, &psk, value, blinding_factor);
+
+            let (mut fee, crossover) = note.try_into().unwrap();
+            fee.gas_limit = gas_limit;
+            fee.gas_price = gas_price;
+
+            (fee, crossover)
+        },
+    )
+}
+
+fn proof() -> impl Strategy<Value = Vec<u8>> {
+    vec(any::<u8>(), 0..128)
+}
+
+// Every call but `Execute`, which wraps one of these in turn.
+fn inner_call() -> impl Strategy<Value = Call> {
+    prop_oneof![
+        (scalar(), any::<u64>(), proof()).prop_map(
+            |(address, value, spend_proof)| {
+                Call::send_to_contract_transparent(address, value, spend_proof)
+            }
+        ),
+        (scalar(), note()).prop_map(|(address, note)| {
+            Call::withdraw_from_transparent(address, note)
+        }),
+        (scalar(), message(), point(), public_key(), proof()).prop_map(
+            |(address, message, r, pk, spend_proof)| {
+                Call::send_to_contract_obfuscated(
+                    address,
+                    message,
+                    r,
+                    pk,
+                    spend_proof,
+                )
+            }
+        ),
+        (
+            scalar(),
+            message(),
+            point(),
+            public_key(),
+            note(),
+            point(),
+            proof()
+        )
+            .prop_map(
+                |(
+                    address,
+                    message,
+                    r,
+                    pk,
+                    note,
+                    input_value_commitment,
+                    spend_proof,
+                )| {
+                    Call::withdraw_from_obfuscated(
+                        address,
+                        message,
+                        r,
+                        pk,
+                        note,
+                        input_value_commitment,
+                        spend_proof,
+                    )
+                }
+            ),
+        (scalar(), scalar(), any::<u64>()).prop_map(|(from, to, value)| {
+            Call::withdraw_from_transparent_to_contract(from, to, value)
+        }),
+    ]
+}
+
+fn call() -> impl Strategy<Value = Call> {
+    prop_oneof![
+        inner_call(),
+        (
+            inner_call(),
+            any::<u8>(),
+            scalar(),
+            vec(scalar(), 0..4),
+            fee_and_crossover(),
+            any::<bool>(),
+            vec(note(), 0..3),
+            proof(),
+        )
+            .prop_map(
+                |(
+                    inner,
+                    contract,
+                    anchor,
+                    nullifiers,
+                    (fee, crossover),
+                    with_crossover,
+                    notes,
+                    spend_proof,
+                )| {
+                    inner
+                        .to_execute::<MemStore>(
+                            ContractId::reserved(contract),
+                            anchor,
+                            nullifiers,
+                            fee,
+                            Some(crossover).filter(|_| with_crossover),
+                            notes,
+                            spend_proof,
+                        )
+                        .unwrap()
+                }
+            ),
+    ]
+}
+
+#[test]
+fn bls_scalar_round_trip() {
+    let mut rng = StdRng::seed_from_u64(0xbeef);
+
+    assert_round_trip(&BlsScalar::random(&mut rng));
+    assert_round_trip(&BlsScalar::zero());
+
+    // All-0xff is above the modulus and must not decode to a reduced value.
+    let store = MemStore::new();
+    let bytes = vec![0xffu8; BlsScalar::zero().encoded_len()];
+    assert!(decode::<BlsScalar>(&bytes, &store).is_err());
+}
+
+#[test]
+fn message_round_trip() {
+    let mut rng = StdRng::seed_from_u64(0xbeef);
+    let psk = SecretSpendKey::random(&mut rng).public_spend_key();
+    let r = JubJubScalar::random(&mut rng);
+
+    assert_round_trip(&Message::new(&mut rng, &r, &psk, 100));
+}
+
+#[test]
+fn fixed_fee_crossover_round_trip() {
+    let mut rng = StdRng::seed_from_u64(0xbeef);
+    let (fee, crossover) = fee_crossover(&mut rng);
+
+    assert_round_trip(&fee);
+    assert_round_trip(&crossover);
+}
+
+proptest! {
+    #[test]
+    fn note_round_trip(note in note()) {
+        assert_round_trip(&note);
+    }
+
+    #[test]
+    fn crossover_round_trip((_, crossover) in fee_and_crossover()) {
+        assert_round_trip(&crossover);
+    }
+
+    #[test]
+    fn fee_round_trip((fee, _) in fee_and_crossover()) {
+        assert_round_trip(&fee);
+    }
+
+    #[test]
+    fn call_round_trip(call in call()) {
+        assert_round_trip(&call);
+    }
+}
@@ -0,0 +1,134 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! The STCT public inputs the contract verifies with must be the ones the
+//! circuit proves.
+
+use std::convert::TryInto;
+use transfer_circuits::SendToContractTransparentCircuit;
+use transfer_contract::stct_public_inputs;
+
+use dusk_bls12_381::BlsScalar;
+use dusk_jubjub::JubJubScalar;
+use dusk_pki::SecretSpendKey;
+use dusk_plonk::circuit::{self, Circuit, PublicInputValue, VerifierData};
+use dusk_plonk::prelude::{Proof, ProverKey, PublicParameters};
+use phoenix_core::{Crossover, Fee, Note};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+struct Stct {
+    pp: PublicParameters,
+    vd: VerifierData,
+    proof: Proof,
+    fee: Fee,
+    crossover: Crossover,
+    value: u64,
+    address: BlsScalar,
+    circuit: SendToContractTransparentCircuit,
+}
+
+fn prove_stct() -> Stct {
+    let mut rng = StdRng::seed_from_u64(2322u64);
+
+    let ssk = SecretSpendKey::random(&mut rng);
+    let vk = ssk.view_key();
+    let psk = ssk.public_spend_key();
+
+    let address = BlsScalar::random(&mut rng);
+    let value = 100;
+    let blinding_factor = JubJubScalar::random(&mut rng);
+
+    let note = Note::obfuscated(&mut rng, &psk, value, blinding_factor);
+    let (mut fee, crossover) = note.try_into().unwrap();
+    fee.gas_limit = 5;
+    fee.gas_price = 1;
+
+    let signature = SendToContractTransparentCircuit::sign(
+        &mut rng, &ssk, &fee, &crossover, value, &address,
+    );
+    let mut circuit = SendToContractTransparentCircuit::new(
+        fee, crossover, &vk, address, signature,
+    )
+    .unwrap();
+
+    let pp = rusk_profile::get_common_reference_string().unwrap();
+    let pp = unsafe { PublicParameters::from_slice_unchecked(pp.as_slice()) };
+
+    let keys = rusk_profile::keys_for("transfer-circuits");
+    let (pk, vd) = keys
+        .get(SendToContractTransparentCircuit::rusk_keys_id())
+        .unwrap();
+    let pk = ProverKey::from_slice(pk.as_slice()).unwrap();
+    let vd = VerifierData::from_slice(vd.as_slice()).unwrap();
+
+    let proof = circuit
+        .gen_proof(&pp, &pk, *rusk::TRANSCRIPT_LABEL)
+        .unwrap();
+
+    Stct {
+        pp,
+        vd,
+        proof,
+        fee,
+        crossover,
+        value,
+        address,
+        circuit,
+    }
+}
+
+fn verify(stct: &Stct, pi: &[BlsScalar]) -> bool {
+    let pi: Vec<PublicInputValue> = pi.iter().map(|s| (*s).into()).collect();
+
+    circuit::verify_proof(
+        &stct.pp,
+        stct.vd.key(),
+        &stct.proof,
+        pi.as_slice(),
+        stct.vd.pi_pos(),
+        *rusk::TRANSCRIPT_LABEL,
+    )
+    .is_ok()
+}
+
+#[test]
+fn contract_inputs_verify_the_circuit_proof() {
+    let stct = prove_stct();
+
+    let pi = stct_public_inputs(
+        &stct.crossover,
+        stct.fee.stealth_address().pk_r(),
+        stct.value,
+        &stct.address,
+    );
+    assert!(verify(&stct, &pi));
+
+    // The circuit lays its inputs out the same way once flattened.
+    let circuit_pi = stct.circuit.public_inputs();
+    assert!(circuit::verify_proof(
+        &stct.pp,
+        stct.vd.key(),
+        &stct.proof,
+        circuit_pi.as_slice(),
+        stct.vd.pi_pos(),
+        *rusk::TRANSCRIPT_LABEL,
+    )
+    .is_ok());
+}
+
+#[test]
+fn contract_inputs_of_another_value_are_refused() {
+    let stct = prove_stct();
+
+    let pi = stct_public_inputs(
+        &stct.crossover,
+        stct.fee.stealth_address().pk_r(),
+        stct.value + 1,
+        &stct.address,
+    );
+    assert!(!verify(&stct, &pi));
+}
@@ -24,7 +24,6 @@ use phoenix_core::{Crossover, Fee, Note};
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 use rusk::vm::{Contract, ContractId, GasMeter, NetworkState};
-use rusk_abi::RuskModule;
 
 const TRANSFER_TREE_DEPTH: usize = 17;
 const CODE: &'static [u8] = include_bytes!(
@@ -57,7 +56,7 @@ impl<S: Store> TransferWrapper<S> {
         let mut rng = StdRng::seed_from_u64(seed);
         let mut network = NetworkState::with_block_height(block_height);
 
-        let rusk_mod = RuskModule::new(store.clone(), &*PP);
+        let rusk_mod = rusk::host_module();
         network.register_host_module(rusk_mod);
 
         let genesis_ssk = SecretSpendKey::random(&mut rng);
@@ -297,8 +296,9 @@ impl<S: Store> TransferWrapper<S> {
         let id = execute_proof.rusk_keys_id();
         let (pk, vd) = Self::circuit_keys(id);
 
-        let proof =
-            execute_proof.gen_proof(&*PP, &pk, b"dusk-network").unwrap();
+        let proof = execute_proof
+            .gen_proof(&*PP, &pk, *rusk::TRANSCRIPT_LABEL)
+            .unwrap();
         let pi = execute_proof.public_inputs();
 
         // Sanity check
@@ -308,7 +308,7 @@ impl<S: Store> TransferWrapper<S> {
             &proof,
             pi.as_slice(),
             vd.pi_pos(),
-            b"dusk-network",
+            *rusk::TRANSCRIPT_LABEL,
         )
         .unwrap();
 
@@ -356,8 +356,9 @@ impl<S: Store> TransferWrapper<S> {
         let (pk, _) = Self::circuit_keys(
             SendToContractTransparentCircuit::rusk_keys_id(),
         );
-        let spend_proof_stct =
-            stct_proof.gen_proof(&*PP, &pk, b"dusk-network").unwrap();
+        let spend_proof_stct = stct_proof
+            .gen_proof(&*PP, &pk, *rusk::TRANSCRIPT_LABEL)
+            .unwrap();
         let spend_proof_stct = spend_proof_stct.to_bytes().to_vec();
 
         let call = Call::send_to_contract_transparent(
@@ -493,8 +494,9 @@ impl<S: Store> TransferWrapper<S> {
         execute_proof.set_fee(&fee).unwrap();
 
         let (pk, _) = Self::circuit_keys(execute_proof.rusk_keys_id());
-        let spend_proof_execute =
-            execute_proof.gen_proof(&*PP, &pk, b"dusk-network").unwrap();
+        let spend_proof_execute = execute_proof
+            .gen_proof(&*PP, &pk, *rusk::TRANSCRIPT_LABEL)
+            .unwrap();
         let spend_proof_execute = spend_proof_execute.to_bytes().to_vec();
 
         let call = Call::withdraw_from_transparent_to_contract(from, to, value)
@@ -20,7 +20,37 @@ use crate::{PublicInput, RuskModule};
 
 impl RuskModule {
     pub fn new(pp: &'static PublicParameters) -> Self {
-        RuskModule { pp }
+        Self::with_label(pp, b"dusk-network")
+    }
+
+    /// Create a module verifying the proofs with the given transcript label.
+    pub fn with_label(
+        pp: &'static PublicParameters,
+        label: &'static [u8],
+    ) -> Self {
+        RuskModule {
+            pp,
+            label,
+            circuit_labels: Vec::new(),
+        }
+    }
+
+    /// Verify the proofs checked against `verifier_data` with `label`
+    /// instead of the module one.
+    pub fn with_circuit_label(
+        mut self,
+        verifier_data: Vec<u8>,
+        label: &'static [u8],
+    ) -> Self {
+        self.circuit_labels.push((verifier_data, label));
+        self
+    }
+
+    fn label_for(&self, verifier_data: &[u8]) -> &'static [u8] {
+        self.circuit_labels
+            .iter()
+            .find(|(vd, _)| vd.as_slice() == verifier_data)
+            .map_or(self.label, |(_, label)| label)
     }
 }
 
@@ -46,6 +76,7 @@ impl HostModule for RuskModule {
                 let proof = Proof::from_slice(&proof)
                     .map_err(|_| CanonError::InvalidEncoding)?;
 
+                let label = self.label_for(&verifier_data);
                 let verifier_data =
                     VerifierData::from_slice(verifier_data.as_slice())
                         .map_err(|_| CanonError::InvalidEncoding)?;
@@ -59,7 +90,7 @@ impl HostModule for RuskModule {
                     &proof,
                     pi.as_slice(),
                     verifier_data.pi_pos().as_slice(),
-                    b"dusk-network",
+                    label,
                 )
                 .is_ok();
 
@@ -16,6 +16,10 @@
 #![no_std]
 #![deny(clippy::all)]
 
+extern crate alloc;
+
+#[cfg(not(target_arch = "wasm32"))]
+use alloc::vec::Vec;
 use canonical::Canon;
 use canonical_derive::Canon;
 use dusk_abi::{ContractId, Module};
@@ -26,11 +30,17 @@ pub use public_input::PublicInput;
 /// Module that exports the ABI for Rusk's Contracts
 ///
 /// Any proof to be verified with this module should use `b"dusk-network` as
-/// transcript initialization
+/// transcript initialization, unless the module is created with another
+/// label through `RuskModule::with_label`, or the label of its circuit is
+/// set through `RuskModule::with_circuit_label`
 #[allow(dead_code)]
 pub struct RuskModule {
     #[cfg(not(target_arch = "wasm32"))]
     pp: &'static dusk_plonk::prelude::PublicParameters,
+    #[cfg(not(target_arch = "wasm32"))]
+    label: &'static [u8],
+    #[cfg(not(target_arch = "wasm32"))]
+    circuit_labels: Vec<(Vec<u8>, &'static [u8])>,
 }
 
 impl RuskModule {
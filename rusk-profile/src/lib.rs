@@ -5,6 +5,7 @@
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
 use dirs::home_dir;
+use fs2::FileExt;
 use sha2::{Digest, Sha256};
 use std::fs::{self, read, remove_file, write, File};
 use std::io;
@@ -48,6 +49,46 @@ impl Keys {
     }
 }
 
+// `EROFS`, the error raised when writing to a read-only filesystem.
+const READ_ONLY_FS_ERROR: i32 = 30;
+
+// Turn the errors caused by a non-writable profile into an actionable one,
+// since they are common in locked-down containers.
+fn not_writable(path: &Path, err: io::Error) -> io::Error {
+    match (err.kind(), err.raw_os_error()) {
+        (io::ErrorKind::PermissionDenied, _)
+        | (_, Some(READ_ONLY_FS_ERROR)) => {
+            warn!("rusk-profile dir is not writable");
+            io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("profile dir not writable: {}", path.display()),
+            )
+        }
+        _ => err,
+    }
+}
+
+// Advisory lock held around the profile writes, so the processes sharing a
+// profile wait for each other instead of interleaving partial files.
+struct WriteLock(File);
+
+impl WriteLock {
+    fn acquire() -> Result<Self, io::Error> {
+        let mut path = get_rusk_profile_dir()?;
+        path.push("write.lock");
+
+        let file = File::create(&path).map_err(|e| not_writable(&path, e))?;
+        file.lock_exclusive()?;
+        Ok(Self(file))
+    }
+}
+
+impl Drop for WriteLock {
+    fn drop(&mut self) {
+        let _ = self.0.unlock();
+    }
+}
+
 fn extension(p: &Path) -> Option<&str> {
     p.extension()?.to_str()
 }
@@ -56,9 +97,17 @@ fn file_stem(p: &Path) -> Option<&str> {
     p.file_stem()?.to_str()
 }
 
+/// Directory of the profile, `.rusk` under `RUSK_PROFILE_PATH` or the home
+/// directory.
+///
+/// `RUSK_PROFILE_PATH` is read at runtime first, then as it was at build
+/// time, so a process can be pointed at another profile than the one it was
+/// built with.
 pub fn get_rusk_profile_dir() -> Result<PathBuf, io::Error> {
-    option_env!("RUSK_PROFILE_PATH")
-        .map_or(home_dir(), |e| Some(PathBuf::from(e)))
+    std::env::var_os("RUSK_PROFILE_PATH")
+        .map(PathBuf::from)
+        .or_else(|| option_env!("RUSK_PROFILE_PATH").map(PathBuf::from))
+        .or_else(home_dir)
         .and_then(|mut p| {
             p.push(".rusk");
             fs::create_dir_all(&p).map(|_| p).ok()
@@ -90,7 +139,8 @@ pub fn set_common_reference_string(buffer: Vec<u8>) -> Result<(), io::Error> {
     let mut profile = get_rusk_profile_dir()?;
     profile.push("dev.crs");
 
-    write(&profile, &buffer)?;
+    let _lock = WriteLock::acquire()?;
+    write(&profile, &buffer).map_err(|e| not_writable(&profile, e))?;
     info!("CRS added to cache");
 
     Ok(())
@@ -100,7 +150,8 @@ pub fn delete_common_reference_string() -> Result<(), io::Error> {
     let mut profile = get_rusk_profile_dir()?;
     profile.push("dev.crs");
 
-    remove_file(&profile)?;
+    let _lock = WriteLock::acquire()?;
+    remove_file(&profile).map_err(|e| not_writable(&profile, e))?;
     info!("CRS removed from cache");
 
     Ok(())
@@ -119,6 +170,7 @@ pub fn clean_outdated_keys(ids: &[[u8; 32]]) -> Result<(), io::Error> {
     info!("Cleaning outdated keys (if any)");
     let ids_as_string: Vec<_> = ids.iter().map(hex::encode).collect();
 
+    let _lock = WriteLock::acquire()?;
     fs::read_dir(&get_rusk_keys_dir()?)?
         .map(|res| res.map(|e| e.path()))
         .filter(|res| res.is_ok())
@@ -135,12 +187,54 @@ pub fn clean_outdated_keys(ids: &[[u8; 32]]) -> Result<(), io::Error> {
                 "Found file {:?} which is not included in the keys list obtained",
                 &p
             );
-            remove_file(get_rusk_keys_dir()?.join(&p))?;
+            remove_file(get_rusk_keys_dir()?.join(&p))
+                .map_err(|e| not_writable(&p, e))?;
             info!("{:?} was successfully removed outdated file", &p);
             Ok(())
         })
 }
 
+/// Ids of the circuits whose prover key and verifier data are both cached,
+/// in ascending order.
+pub fn cached_circuit_ids() -> Result<Vec<[u8; 32]>, io::Error> {
+    let mut ids: Vec<[u8; 32]> = fs::read_dir(&get_rusk_keys_dir()?)?
+        .map(|res| res.map(|e| e.path()))
+        .filter(|res| res.is_ok())
+        .map(|res| res.unwrap())
+        .filter(|p| p.is_file() && extension(&p) == Some("vd"))
+        .filter_map(|p| {
+            let stem = file_stem(&p)?;
+            let mut id = [0u8; 32];
+            hex::decode_to_slice(stem, &mut id).ok()?;
+            Some(id)
+        })
+        .filter(|id| keys_for(id).is_ok())
+        .collect();
+
+    ids.sort_unstable();
+    Ok(ids)
+}
+
+/// Hash of all the cached `(circuit_id, verifier_data)` pairs, taken in
+/// ascending circuit id order.
+///
+/// Two profiles holding the same circuit keys produce the same fingerprint.
+pub fn keys_fingerprint() -> Result<[u8; 32], io::Error> {
+    let mut hasher = Sha256::new();
+
+    for id in cached_circuit_ids()? {
+        let vd = Keys(id).get_verifier()?;
+
+        hasher.update(&id);
+        hasher.update(&(vd.len() as u64).to_le_bytes());
+        hasher.update(&vd);
+    }
+
+    let mut fingerprint = [0u8; 32];
+    fingerprint.copy_from_slice(&hasher.finalize());
+    Ok(fingerprint)
+}
+
 pub fn keys_for(id: &[u8; 32]) -> Result<Keys, io::Error> {
     let mut dir = get_rusk_keys_dir()?;
     dir.push(hex::encode(id));
@@ -155,6 +249,11 @@ pub fn keys_for(id: &[u8; 32]) -> Result<Keys, io::Error> {
     }
 }
 
+/// Raw bytes of the cached prover key of a circuit, as compiled by the node.
+pub fn proving_key_for(id: &[u8; 32]) -> Option<Vec<u8>> {
+    keys_for(id).and_then(|keys| keys.get_prover()).ok()
+}
+
 pub fn add_keys_for(
     id: &[u8; 32],
     pk: Vec<u8>,
@@ -166,17 +265,204 @@ pub fn add_keys_for(
     let pk_file = dir.with_extension("pk");
     let vd_file = dir.with_extension("vd");
 
-    File::create(&pk_file)?.write_all(&pk)?;
+    // Both keys are written under the lock, so they always come in pairs.
+    let _lock = WriteLock::acquire()?;
+    File::create(&pk_file)
+        .and_then(|mut file| file.write_all(&pk))
+        .map_err(|e| not_writable(&pk_file, e))?;
     info!("Entry added: {:?}", pk_file);
-    File::create(&vd_file)?.write_all(&vd)?;
+    File::create(&vd_file)
+        .and_then(|mut file| file.write_all(&vd))
+        .map_err(|e| not_writable(&vd_file, e))?;
     info!("Entry added: {:?}", vd_file);
 
     Ok(())
 }
 
+fn bundle_entry_hash(id: &[u8; 32], pk: &[u8], vd: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(id);
+    hasher.update(pk);
+    hasher.update(vd);
+
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&hasher.finalize());
+    hash
+}
+
+fn take<'a>(bytes: &mut &'a [u8], n: usize) -> Result<&'a [u8], io::Error> {
+    if bytes.len() < n {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "truncated keys bundle",
+        ));
+    }
+    let (head, tail) = bytes.split_at(n);
+    *bytes = tail;
+    Ok(head)
+}
+
+fn take_len(bytes: &mut &[u8]) -> Result<usize, io::Error> {
+    let mut len = [0u8; 8];
+    len.copy_from_slice(take(bytes, 8)?);
+    Ok(u64::from_le_bytes(len) as usize)
+}
+
+/// SHA-256 of the cached verifier data of a circuit.
+///
+/// The verifier data is derived along with the prover key, so it tells the
+/// keys apart without reading the much larger prover key.
+pub fn verifier_data_hash(id: &[u8; 32]) -> Result<[u8; 32], io::Error> {
+    let vd = keys_for(id)?.get_verifier()?;
+
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&Sha256::digest(&vd));
+    Ok(hash)
+}
+
+/// Serialize the cached keys of `ids` into a bundle that
+/// [`add_keys_from_bundle`] can load into another profile.
+///
+/// Every entry is laid out as `id || pk_len || pk || vd_len || vd || hash`,
+/// with the lengths as little-endian `u64` and `hash` being the SHA-256 of
+/// `id || pk || vd`.
+pub fn keys_bundle(ids: &[[u8; 32]]) -> Result<Vec<u8>, io::Error> {
+    let mut bundle = vec![];
+
+    for id in ids {
+        let keys = keys_for(id)?;
+        let pk = keys.get_prover()?;
+        let vd = keys.get_verifier()?;
+
+        bundle.extend_from_slice(id);
+        bundle.extend_from_slice(&(pk.len() as u64).to_le_bytes());
+        bundle.extend_from_slice(&pk);
+        bundle.extend_from_slice(&(vd.len() as u64).to_le_bytes());
+        bundle.extend_from_slice(&vd);
+        bundle.extend_from_slice(&bundle_entry_hash(id, &pk, &vd));
+    }
+
+    Ok(bundle)
+}
+
+/// SHA-256 of a whole bundle, the digest [`add_keys_from_bundle`] checks
+/// it against.
+pub fn bundle_hash(bundle: &[u8]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&Sha256::digest(bundle));
+    hash
+}
+
+/// Add to the cache the entries of a bundle built by [`keys_bundle`].
+///
+/// The bundle is refused unless its [`bundle_hash`] is `expected_hash`,
+/// which must come from a trusted source rather than along with the bundle.
+/// Only the entries whose id is one of `ids` and whose hash matches their
+/// contents are added, the others are skipped. Returns the ids added.
+pub fn add_keys_from_bundle(
+    mut bundle: &[u8],
+    expected_hash: &[u8; 32],
+    ids: &[[u8; 32]],
+) -> Result<Vec<[u8; 32]>, io::Error> {
+    if &bundle_hash(bundle) != expected_hash {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "keys bundle doesn't match the expected hash",
+        ));
+    }
+    let mut added = vec![];
+
+    while !bundle.is_empty() {
+        let mut id = [0u8; 32];
+        id.copy_from_slice(take(&mut bundle, 32)?);
+        let pk_len = take_len(&mut bundle)?;
+        let pk = take(&mut bundle, pk_len)?;
+        let vd_len = take_len(&mut bundle)?;
+        let vd = take(&mut bundle, vd_len)?;
+        let hash = take(&mut bundle, 32)?;
+
+        if !ids.contains(&id) {
+            warn!("Skipping unexpected bundle entry {}", hex::encode(id));
+        } else if hash != bundle_entry_hash(&id, pk, vd) {
+            warn!("Skipping corrupted bundle entry {}", hex::encode(id));
+        } else {
+            add_keys_for(&id, pk.to_vec(), vd.to_vec())?;
+            added.push(id);
+        }
+    }
+
+    Ok(added)
+}
+
+/// Target features of the build that last compiled keys into the cache, as
+/// listed by `CARGO_CFG_TARGET_FEATURE`.
+///
+/// The record lives in the profile dir, so cleaning the keys dir of the
+/// outdated keys leaves it in place.
+pub fn keys_target_features() -> Option<String> {
+    let mut file = get_rusk_profile_dir().ok()?;
+    file.push("target_features");
+
+    fs::read_to_string(file).ok()
+}
+
+pub fn set_keys_target_features(features: &str) -> Result<(), io::Error> {
+    let mut file = get_rusk_profile_dir()?;
+    file.push("target_features");
+
+    let _lock = WriteLock::acquire()?;
+    write(&file, features).map_err(|e| not_writable(&file, e))
+}
+
+/// Summary of the last build that checked the keys cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuildSummary {
+    /// Circuits whose keys were compiled, the cached ones aside.
+    pub circuits_compiled: usize,
+    /// Seconds spent checking the cache and compiling the keys.
+    pub compile_secs: u64,
+    /// Whether the CRS was regenerated instead of read from the cache.
+    pub crs_regenerated: bool,
+    /// Seconds since the Unix epoch when the build finished.
+    pub timestamp: u64,
+}
+
+/// Summary left by the last build, `None` if no build recorded one.
+pub fn build_summary() -> Option<BuildSummary> {
+    let mut file = get_rusk_profile_dir().ok()?;
+    file.push("build_summary");
+
+    let summary = fs::read_to_string(file).ok()?;
+    let mut fields = summary.lines().map(str::trim);
+    let mut next = || fields.next()?.parse::<u64>().ok();
+
+    Some(BuildSummary {
+        circuits_compiled: next()? as usize,
+        compile_secs: next()?,
+        crs_regenerated: next()? != 0,
+        timestamp: next()?,
+    })
+}
+
+pub fn set_build_summary(summary: &BuildSummary) -> Result<(), io::Error> {
+    let mut file = get_rusk_profile_dir()?;
+    file.push("build_summary");
+
+    let summary = format!(
+        "{}\n{}\n{}\n{}\n",
+        summary.circuits_compiled,
+        summary.compile_secs,
+        summary.crs_regenerated as u8,
+        summary.timestamp
+    );
+    let _lock = WriteLock::acquire()?;
+    write(&file, summary).map_err(|e| not_writable(&file, e))
+}
+
 pub fn clear_all_keys() -> Result<(), io::Error> {
     info!("Clearing all the Keys folder contents");
 
+    let _lock = WriteLock::acquire()?;
     fs::read_dir(&get_rusk_keys_dir()?)?
         .map(|res| res.map(|e| e.path()))
         .filter(|res| res.is_ok())
@@ -185,6 +471,6 @@ pub fn clear_all_keys() -> Result<(), io::Error> {
         .filter(|p| matches!(extension(&p), Some("pk" | "vd")))
         .try_for_each(|path| {
             info!("Removing {:?}", path);
-            remove_file(path)
+            remove_file(&path).map_err(|e| not_writable(&path, e))
         })
 }
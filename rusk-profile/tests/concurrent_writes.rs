@@ -0,0 +1,49 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::fs::remove_dir_all;
+use std::thread;
+
+const ID: [u8; 32] = [0xfe; 32];
+
+#[test]
+fn concurrent_writers_never_mix_keys() {
+    // The keys are written to a profile of their own rather than the real
+    // one. This is the only test of the file, so nothing else reads the
+    // variable meanwhile.
+    let mut profile = std::env::temp_dir();
+    profile.push(format!("rusk-profile-writes-{}", std::process::id()));
+    std::env::set_var("RUSK_PROFILE_PATH", &profile);
+
+    let writers: Vec<_> = (1..=2u8)
+        .map(|writer| {
+            thread::spawn(move || {
+                for _ in 0..8 {
+                    let pk = vec![writer; 1 << 20];
+                    let vd = vec![writer; 1 << 16];
+                    rusk_profile::add_keys_for(&ID, pk, vd)
+                        .expect("Failed to write the keys");
+                }
+            })
+        })
+        .collect();
+    writers
+        .into_iter()
+        .for_each(|writer| writer.join().expect("A writer panicked"));
+
+    let keys = rusk_profile::keys_for(&ID).expect("Keys not written");
+    let pk = keys.get_prover().expect("Failed to read the prover key");
+    let vd = keys
+        .get_verifier()
+        .expect("Failed to read the verifier data");
+
+    // The last writer wrote both files, and nobody wrote in between.
+    assert_eq!(pk.len(), 1 << 20);
+    assert_eq!(vd.len(), 1 << 16);
+    assert!(pk.iter().chain(vd.iter()).all(|&b| b == pk[0]));
+
+    remove_dir_all(profile).unwrap();
+}
@@ -6,17 +6,20 @@
 
 use bid_circuits::BidCorrectnessCircuit;
 use blindbid_circuits::BlindBidCircuit;
-use dusk_blindbid::{Bid, Score};
-use dusk_bls12_381::BlsScalar;
-use dusk_jubjub::{JubJubAffine, GENERATOR_EXTENDED, GENERATOR_NUMS_EXTENDED};
-use dusk_pki::{PublicSpendKey, SecretSpendKey};
 use dusk_plonk::prelude::*;
 use dusk_poseidon::tree::PoseidonBranch;
 use lazy_static::lazy_static;
-use profile_tooling::CircuitLoader;
+use profile_tooling::{CircuitLoader, CircuitSize};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use tracing::{info, warn, Level};
 use tracing_subscriber::FmtSubscriber;
 
+#[path = "src/lib/circuits/dummy.rs"]
+mod dummy;
+
+// Set when the CRS is generated by this build, for the build summary.
+static CRS_REGENERATED: AtomicBool = AtomicBool::new(false);
+
 lazy_static! {
     static ref PUB_PARAMS: PublicParameters = {
         match rusk_profile::get_common_reference_string() {
@@ -26,7 +29,29 @@ lazy_static! {
                 PublicParameters::from_slice_unchecked(&buff[..])
             },
 
+            // A corrupt CRS is only replaced when explicitly asked, the node
+            // would otherwise ship with a trusted setup of its own.
+            Ok(_) if option_env!("RUSK_DEV_CRS").unwrap_or("0") == "0" => {
+                panic!("The cached CRS failed the integrity check. Restore it, or set RUSK_DEV_CRS to regenerate it")
+            }
+
             _ => {
+                // Downloading the CRS is way cheaper than generating it.
+                if let Some(url) = option_env!("RUSK_CRS_URL") {
+                    match profile_tooling::download_crs(url) {
+                        Ok(buff) => {
+                            rusk_profile::set_common_reference_string(
+                                buff.clone(),
+                            )
+                            .expect("Unable to write the CRS");
+                            return unsafe {
+                                PublicParameters::from_slice_unchecked(&buff)
+                            };
+                        }
+                        Err(e) => warn!("Failed downloading the CRS: {}", e),
+                    }
+                }
+
                 info!("New CRS needs to be generated and cached");
 
                 use rand::rngs::StdRng;
@@ -43,6 +68,7 @@ lazy_static! {
                     pp.to_raw_var_bytes(),
                 )
                 .expect("Unable to write the CRS");
+                CRS_REGENERATED.store(true, Ordering::SeqCst);
 
                 pp
             }
@@ -79,12 +105,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let subscriber = FmtSubscriber::builder()
         // all spans/events with a level higher than TRACE (e.g, debug, info,
         // warn, etc.) will be written to stdout.
-        .with_max_level(Level::TRACE)
-        // completes the builder.
-        .finish();
+        .with_max_level(Level::TRACE);
 
-    tracing::subscriber::set_global_default(subscriber)
-        .expect("setting default subscriber failed");
+    // JSON lines are easier to aggregate, pretty output stays the default.
+    match option_env!("RUSK_LOG_FORMAT").unwrap_or("pretty") {
+        "json" => {
+            tracing::subscriber::set_global_default(subscriber.json().finish())
+        }
+        "pretty" => tracing::subscriber::set_global_default(
+            // completes the builder.
+            subscriber.finish(),
+        ),
+        format => panic!("Unknown RUSK_LOG_FORMAT `{}`", format),
+    }
+    .expect("setting default subscriber failed");
 
     if option_env!("RUSK_PROFILE_PATH").is_none() {
         panic!("RUSK_PROFILE_PATH env var is not set. Please run `source .env` to set it");
@@ -95,6 +129,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // build script.
     lazy_static::initialize(&PUB_PARAMS);
 
+    // The keys compiled below are tied to the target features the node is
+    // built for, so the node can tell when it loads keys built otherwise.
+    let target_features = profile_tooling::target_features();
+    info!("Target features: {}", target_features);
+    println!("cargo:rustc-env=RUSK_TARGET_FEATURES={}", target_features);
+
     // Compile protos for tonic
     tonic_build::compile_protos("../schema/rusk.proto")?;
 
@@ -112,25 +152,28 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         info!("Keys folder contents were removed successfully!");
     };
 
-    profile_tooling::run_circuit_keys_checks(vec![
-        &BidCircuitLoader {},
-        &BlindBidCircuitLoader {},
-        &StctCircuitLoader {},
-        &StcoCircuitLoader {},
-        &WfoCircuitLoader {},
-        &ExecuteOneZeroCircuitLoader {},
-        &ExecuteOneOneCircuitLoader {},
-        &ExecuteOneTwoCircuitLoader {},
-        &ExecuteTwoZeroCircuitLoader {},
-        &ExecuteTwoOneCircuitLoader {},
-        &ExecuteTwoTwoCircuitLoader {},
-        &ExecuteThreeZeroCircuitLoader {},
-        &ExecuteThreeOneCircuitLoader {},
-        &ExecuteThreeTwoCircuitLoader {},
-        &ExecuteFourZeroCircuitLoader {},
-        &ExecuteFourOneCircuitLoader {},
-        &ExecuteFourTwoCircuitLoader {},
-    ])?;
+    profile_tooling::run_circuit_keys_checks(
+        &PUB_PARAMS,
+        vec![
+            &BidCircuitLoader {},
+            &BlindBidCircuitLoader {},
+            &StctCircuitLoader {},
+            &StcoCircuitLoader {},
+            &WfoCircuitLoader {},
+            &ExecuteOneZeroCircuitLoader {},
+            &ExecuteOneOneCircuitLoader {},
+            &ExecuteOneTwoCircuitLoader {},
+            &ExecuteTwoZeroCircuitLoader {},
+            &ExecuteTwoOneCircuitLoader {},
+            &ExecuteTwoTwoCircuitLoader {},
+            &ExecuteThreeZeroCircuitLoader {},
+            &ExecuteThreeOneCircuitLoader {},
+            &ExecuteThreeTwoCircuitLoader {},
+            &ExecuteFourZeroCircuitLoader {},
+            &ExecuteFourOneCircuitLoader {},
+            &ExecuteFourTwoCircuitLoader {},
+        ],
+    )?;
 
     Ok(())
 }
@@ -149,26 +192,17 @@ mod bid {
             "BidCorrectness"
         }
 
+        fn circuit_size(
+            &self,
+        ) -> Result<CircuitSize, Box<dyn std::error::Error>> {
+            profile_tooling::circuit_size(&mut dummy::bid())
+        }
+
         fn compile_circuit(
             &self,
+            pub_params: &PublicParameters,
         ) -> Result<(Vec<u8>, Vec<u8>), Box<dyn std::error::Error>> {
-            let pub_params = &PUB_PARAMS;
-            let value = JubJubScalar::from(100000_u64);
-            let blinder = JubJubScalar::from(50000_u64);
-
-            let c = JubJubAffine::from(
-                (GENERATOR_EXTENDED * value)
-                    + (GENERATOR_NUMS_EXTENDED * blinder),
-            );
-
-            let mut circuit = BidCorrectnessCircuit {
-                commitment: c,
-                value,
-                blinder,
-            };
-
-            let (pk, vd) = circuit.compile(&pub_params)?;
-            Ok((pk.to_var_bytes(), vd.to_var_bytes()))
+            profile_tooling::compile(&mut dummy::bid(), pub_params)
         }
     }
 }
@@ -185,82 +219,26 @@ mod blindbid {
             "BlindBid"
         }
 
+        fn circuit_size(
+            &self,
+        ) -> Result<CircuitSize, Box<dyn std::error::Error>> {
+            let branch = PoseidonBranch::<17>::default();
+            profile_tooling::circuit_size(&mut dummy::blindbid(&branch))
+        }
+
         fn compile_circuit(
             &self,
+            pub_params: &PublicParameters,
         ) -> Result<(Vec<u8>, Vec<u8>), Box<dyn std::error::Error>> {
-            let pub_params = &PUB_PARAMS;
-
-            // Generate a correct Bid
-            let secret = JubJubScalar::random(&mut rand::thread_rng());
-            let secret_k = BlsScalar::random(&mut rand::thread_rng());
-            let bid = random_bid(&secret, secret_k);
-            let secret: JubJubAffine = (GENERATOR_EXTENDED * secret).into();
-
-            // Generate fields for the Bid & required by the compute_score
-            let consensus_round_seed = 50u64;
-            let latest_consensus_round = 50u64;
-            let latest_consensus_step = 50u64;
-
-            // Extract the branch
             let branch = PoseidonBranch::<17>::default();
-
-            // Generate a `Score` for our Bid with the consensus parameters
-            let score = Score::compute(
-                &bid,
-                &secret,
-                secret_k,
-                *branch.root(),
-                BlsScalar::from(consensus_round_seed),
-                latest_consensus_round,
-                latest_consensus_step,
-            )
-            .expect("Score gen error");
-
-            let mut circuit = BlindBidCircuit {
-                bid,
-                score,
-                secret_k,
-                secret,
-                seed: BlsScalar::from(consensus_round_seed),
-                latest_consensus_round: BlsScalar::from(latest_consensus_round),
-                latest_consensus_step: BlsScalar::from(latest_consensus_step),
-                branch: &branch,
-            };
-
-            let (pk, vd) = circuit.compile(&pub_params)?;
-            Ok((pk.to_var_bytes(), vd.to_var_bytes()))
+            profile_tooling::compile(&mut dummy::blindbid(&branch), pub_params)
         }
     }
-
-    fn random_bid(secret: &JubJubScalar, secret_k: BlsScalar) -> Bid {
-        let mut rng = rand::thread_rng();
-        let pk_r = PublicSpendKey::from(SecretSpendKey::random(&mut rng));
-        let stealth_addr = pk_r.gen_stealth_address(&secret);
-        let secret = GENERATOR_EXTENDED * secret;
-        let value = 60_000u64;
-        let value = JubJubScalar::from(value);
-        // Set the timestamps as the max values so the proofs do not fail for
-        // them (never expired or non-elegible).
-        let elegibility_ts = u64::MAX;
-        let expiration_ts = u64::MAX;
-
-        Bid::new(
-            &mut rng,
-            &stealth_addr,
-            &value,
-            &secret.into(),
-            secret_k,
-            elegibility_ts,
-            expiration_ts,
-        )
-        .expect("Error generating a Bid")
-    }
 }
 
 mod transfer {
     use super::*;
-    use phoenix_core::{Message, Note};
-    use std::convert::{TryFrom, TryInto};
+    use std::convert::TryFrom;
     use transfer_circuits::*;
 
     pub struct StctCircuitLoader;
@@ -273,45 +251,17 @@ mod transfer {
             "STCT"
         }
 
+        fn circuit_size(
+            &self,
+        ) -> Result<CircuitSize, Box<dyn std::error::Error>> {
+            profile_tooling::circuit_size(&mut dummy::stct())
+        }
+
         fn compile_circuit(
             &self,
+            pub_params: &PublicParameters,
         ) -> Result<(Vec<u8>, Vec<u8>), Box<dyn std::error::Error>> {
-            let pub_params = &PUB_PARAMS;
-            let rng = &mut rand::thread_rng();
-
-            let c_ssk = SecretSpendKey::random(rng);
-            let c_vk = c_ssk.view_key();
-            let c_psk = c_ssk.public_spend_key();
-
-            let c_address = BlsScalar::random(rng);
-
-            let c_value = 100;
-            let c_blinding_factor = JubJubScalar::random(rng);
-
-            let c_note =
-                Note::obfuscated(rng, &c_psk, c_value, c_blinding_factor);
-            let (mut fee, crossover) = c_note
-                .try_into()
-                .expect("Failed to convert note into fee/crossover pair!");
-
-            fee.gas_limit = 5;
-            fee.gas_price = 1;
-
-            let c_signature = SendToContractTransparentCircuit::sign(
-                rng, &c_ssk, &fee, &crossover, c_value, &c_address,
-            );
-
-            let mut circuit = SendToContractTransparentCircuit::new(
-                fee,
-                crossover,
-                &c_vk,
-                c_address,
-                c_signature,
-            )
-            .expect("Failed to create STCT circuit!");
-
-            let (pk, vd) = circuit.compile(&pub_params)?;
-            Ok((pk.to_var_bytes(), vd.to_var_bytes()))
+            profile_tooling::compile(&mut dummy::stct(), pub_params)
         }
     }
 
@@ -325,52 +275,17 @@ mod transfer {
             "STCO"
         }
 
+        fn circuit_size(
+            &self,
+        ) -> Result<CircuitSize, Box<dyn std::error::Error>> {
+            profile_tooling::circuit_size(&mut dummy::stco())
+        }
+
         fn compile_circuit(
             &self,
+            pub_params: &PublicParameters,
         ) -> Result<(Vec<u8>, Vec<u8>), Box<dyn std::error::Error>> {
-            let pub_params = &PUB_PARAMS;
-            let rng = &mut rand::thread_rng();
-
-            let ssk = SecretSpendKey::random(rng);
-            let vk = ssk.view_key();
-            let psk = ssk.public_spend_key();
-
-            let c_address = BlsScalar::random(rng);
-
-            let c_value = 100;
-            let c_blinding_factor = JubJubScalar::random(rng);
-            let c_note =
-                Note::obfuscated(rng, &psk, c_value, c_blinding_factor);
-            let (mut fee, crossover) = c_note
-                .try_into()
-                .expect("Failed to convert note into fee/crossover pair!");
-
-            fee.gas_limit = 5;
-            fee.gas_price = 1;
-
-            let message_r = JubJubScalar::random(rng);
-            let message_value = 100;
-            let message = Message::new(rng, &message_r, &psk, message_value);
-
-            let c_signature = SendToContractObfuscatedCircuit::sign(
-                rng, &ssk, &fee, &crossover, &message, &c_address,
-            );
-
-            let mut circuit = SendToContractObfuscatedCircuit::new(
-                fee,
-                crossover,
-                &vk,
-                c_signature,
-                true,
-                message,
-                &psk,
-                message_r,
-                c_address,
-            )
-            .expect("Failed to generate circuit!");
-
-            let (pk, vd) = circuit.compile(&pub_params)?;
-            Ok((pk.to_var_bytes(), vd.to_var_bytes()))
+            profile_tooling::compile(&mut dummy::stco(), pub_params)
         }
     }
 
@@ -384,53 +299,30 @@ mod transfer {
             "WFO"
         }
 
+        fn circuit_size(
+            &self,
+        ) -> Result<CircuitSize, Box<dyn std::error::Error>> {
+            profile_tooling::circuit_size(&mut dummy::wfo())
+        }
+
         fn compile_circuit(
             &self,
+            pub_params: &PublicParameters,
         ) -> Result<(Vec<u8>, Vec<u8>), Box<dyn std::error::Error>> {
-            let pub_params = &PUB_PARAMS;
-            let rng = &mut rand::thread_rng();
-
-            let i_ssk = SecretSpendKey::random(rng);
-            let i_vk = i_ssk.view_key();
-            let i_psk = i_ssk.public_spend_key();
-            let i_value = 100;
-            let i_blinding_factor = JubJubScalar::random(rng);
-            let i_note =
-                Note::obfuscated(rng, &i_psk, i_value, i_blinding_factor);
-
-            let c_ssk = SecretSpendKey::random(rng);
-            let c_psk = c_ssk.public_spend_key();
-            let c_r = JubJubScalar::random(rng);
-            let c_value = 25;
-            let c = Message::new(rng, &c_r, &c_psk, c_value);
-
-            let o_ssk = SecretSpendKey::random(rng);
-            let o_vk = o_ssk.view_key();
-            let o_psk = o_ssk.public_spend_key();
-            let o_value = 75;
-            let o_blinding_factor = JubJubScalar::random(rng);
-            let o_note =
-                Note::obfuscated(rng, &o_psk, o_value, o_blinding_factor);
-
-            let mut circuit = WithdrawFromObfuscatedCircuit::new(
-                &i_note,
-                Some(&i_vk),
-                &c,
-                c_r,
-                &c_psk,
-                &o_note,
-                Some(&o_vk),
-            )
-            .expect("Failed to generate circuit!");
-
-            let (pk, vd) = circuit.compile(&pub_params)?;
-            Ok((pk.to_var_bytes(), vd.to_var_bytes()))
+            profile_tooling::compile(&mut dummy::wfo(), pub_params)
         }
     }
 
     macro_rules! execute_circuit_variant {
         ($c:ident,$b:ident,$s:expr,$i:expr,$o:expr) => {
             pub struct $c;
+
+            impl $c {
+                fn circuit(&self) -> Result<$b, Box<dyn std::error::Error>> {
+                    Ok($b::try_from(dummy::execute($i, $o)?)?)
+                }
+            }
+
             impl CircuitLoader for $c {
                 fn circuit_id(&self) -> &[u8; 32] {
                     &$b::CIRCUIT_ID
@@ -440,20 +332,18 @@ mod transfer {
                     $s
                 }
 
+                fn circuit_size(
+                    &self,
+                ) -> Result<CircuitSize, Box<dyn std::error::Error>> {
+                    profile_tooling::circuit_size(&mut self.circuit()?)
+                }
+
                 fn compile_circuit(
                     &self,
+                    pub_params: &PublicParameters,
                 ) -> Result<(Vec<u8>, Vec<u8>), Box<dyn std::error::Error>>
                 {
-                    let pub_params = &PUB_PARAMS;
-                    let rng = &mut rand::thread_rng();
-
-                    let circuit = ExecuteCircuit::create_dummy_circuit(
-                        rng, $i, $o, true,
-                    )?;
-                    let mut circuit = $b::try_from(circuit)?;
-
-                    let (pk, vd) = circuit.compile(&pub_params)?;
-                    Ok((pk.to_var_bytes(), vd.to_var_bytes()))
+                    profile_tooling::compile(&mut self.circuit()?, pub_params)
                 }
             }
         };
@@ -547,17 +437,149 @@ mod transfer {
 
 mod profile_tooling {
     use super::*;
-
-    pub trait CircuitLoader {
+    use dusk_bytes::Serializable;
+    use fs2::FileExt;
+    use rayon::prelude::*;
+    use rayon::ThreadPoolBuilder;
+    use std::collections::BTreeMap;
+    use std::io::Read;
+    use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+    // Circuits compiled by this build, for the build summary.
+    static COMPILED: AtomicUsize = AtomicUsize::new(0);
+
+    pub trait CircuitLoader: Sync {
         fn circuit_id(&self) -> &[u8; 32];
 
         fn circuit_name(&self) -> &'static str;
 
+        /// Label separating the transcripts of the circuit from the other
+        /// ones, appended to the network label. The circuits without one
+        /// share the network label.
+        fn transcript_label(&self) -> Option<&'static [u8]> {
+            None
+        }
+
+        /// Size of the circuit once its gadget ran.
+        fn circuit_size(
+            &self,
+        ) -> Result<CircuitSize, Box<dyn std::error::Error>>;
+
         fn compile_circuit(
             &self,
+            pp: &PublicParameters,
         ) -> Result<(Vec<u8>, Vec<u8>), Box<dyn std::error::Error>>;
     }
 
+    /// Gates a circuit is made of, and the size it is padded to when
+    /// compiled, i.e. the CRS degree it needs.
+    #[derive(Debug, Clone, Copy)]
+    pub struct CircuitSize {
+        pub gates: usize,
+        pub padded: usize,
+    }
+
+    /// Size of `circuit`, its gates read from a composer its gadget ran on.
+    pub fn circuit_size<C: Circuit>(
+        circuit: &mut C,
+    ) -> Result<CircuitSize, Box<dyn std::error::Error>> {
+        let mut composer = StandardComposer::new();
+        circuit.gadget(&mut composer)?;
+
+        Ok(CircuitSize {
+            gates: composer.circuit_size(),
+            padded: circuit.padded_circuit_size(),
+        })
+    }
+
+    /// Compile `circuit` into its prover key and verifier data bytes.
+    pub fn compile<C: Circuit>(
+        circuit: &mut C,
+        pp: &PublicParameters,
+    ) -> Result<(Vec<u8>, Vec<u8>), Box<dyn std::error::Error>> {
+        let (pk, vd) = circuit.compile(pp)?;
+        Ok((pk.to_var_bytes(), vd.to_var_bytes()))
+    }
+
+    /// CRS available to compile the circuits.
+    ///
+    /// Next to the profile one, `RUSK_CRS_FILES` can list smaller CRS as
+    /// comma separated `degree=path` pairs. They must come from the same
+    /// trusted setup as the profile CRS, otherwise the keys compiled with
+    /// them won't verify against it, so the build fails on any that doesn't.
+    struct Crs<'a> {
+        profile: &'a PublicParameters,
+        by_degree: BTreeMap<usize, PublicParameters>,
+    }
+
+    impl<'a> Crs<'a> {
+        fn new(
+            profile: &'a PublicParameters,
+        ) -> Result<Self, Box<dyn std::error::Error>> {
+            let mut by_degree = BTreeMap::new();
+
+            let files = option_env!("RUSK_CRS_FILES").unwrap_or("");
+            for entry in files.split(',').filter(|e| !e.is_empty()) {
+                let (degree, path) = match entry.find('=') {
+                    Some(i) => (&entry[..i], &entry[i + 1..]),
+                    None => {
+                        return Err(format!("Bad CRS entry {}", entry).into())
+                    }
+                };
+                let degree = degree.parse::<usize>()?;
+
+                info!("Loading the CRS of degree {} from {}", degree, path);
+                let pp = PublicParameters::from_slice(&std::fs::read(path)?)?;
+                if pp.max_degree() < degree {
+                    return Err(format!(
+                        "{} holds a CRS of degree {}, not {}",
+                        path,
+                        pp.max_degree(),
+                        degree
+                    )
+                    .into());
+                }
+
+                // The CRS of a setup all open with the same g2 points, and
+                // share the powers of g they both hold.
+                let shared = degree.min(profile.max_degree());
+                let (ck, ok) = pp.trim(shared)?;
+                let (profile_ck, profile_ok) = profile.trim(shared)?;
+                if ok.to_bytes() != profile_ok.to_bytes()
+                    || ck.to_var_bytes() != profile_ck.to_var_bytes()
+                {
+                    return Err(format!(
+                        "{} holds a CRS of another setup than the profile one",
+                        path
+                    )
+                    .into());
+                }
+                by_degree.insert(degree, pp);
+            }
+
+            Ok(Self { profile, by_degree })
+        }
+
+        /// Smallest CRS holding `degree`, the profile one if none does.
+        fn for_degree(&self, degree: usize) -> &PublicParameters {
+            self.by_degree
+                .range(degree..)
+                .next()
+                .map(|(_, pp)| pp)
+                .unwrap_or(self.profile)
+        }
+    }
+
+    pub fn target_features() -> String {
+        std::env::var("CARGO_CFG_TARGET_FEATURE").unwrap_or_default()
+    }
+
+    // When set, the profile is expected to hold every key already and is
+    // never written to.
+    fn read_only() -> bool {
+        option_env!("RUSK_PROFILE_READ_ONLY").unwrap_or("0") != "0"
+    }
+
     fn clear_outdated_keys(
         loader_list: &[&dyn CircuitLoader],
     ) -> Result<(), Box<dyn std::error::Error>> {
@@ -570,48 +592,390 @@ mod profile_tooling {
         Ok(rusk_profile::clean_outdated_keys(&id_list)?)
     }
 
+    // When set, every circuit is checked and all the failures are reported
+    // together instead of stopping at the first one.
+    fn collect_errors() -> bool {
+        option_env!("RUSK_KEYS_COLLECT_ERRORS").unwrap_or("0") != "0"
+    }
+
+    fn check_circuit_keys(
+        crs: &Crs,
+        loader: &dyn CircuitLoader,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        info!("{} Keys cache checking stage", loader.circuit_name());
+        match rusk_profile::keys_for(loader.circuit_id()) {
+            Ok(_) => {
+                info!("{} already loaded correctly!", loader.circuit_name());
+                Ok(())
+            }
+            _ if read_only() => Err(format!(
+                "{} not cached and the profile is read-only",
+                loader.circuit_name()
+            )
+            .into()),
+            _ => {
+                warn!("{} not cached!", loader.circuit_name());
+                let CircuitSize {
+                    gates,
+                    padded: degree,
+                } = loader.circuit_size()?;
+                let pp = crs.for_degree(degree);
+                info!(
+                    "{}: {} gates, needs degree {}, CRS degree {}",
+                    loader.circuit_name(),
+                    gates,
+                    degree,
+                    pp.max_degree()
+                );
+                // Don't even try to compile a circuit the CRS can't hold.
+                if degree > pp.max_degree() {
+                    return Err(format!(
+                        "{} needs a CRS of degree {}, got {}",
+                        loader.circuit_name(),
+                        degree,
+                        pp.max_degree()
+                    )
+                    .into());
+                }
+                info!(
+                    "Compiling {} and adding to the cache",
+                    loader.circuit_name()
+                );
+                let (pk, vd) = loader.compile_circuit(pp)?;
+                rusk_profile::add_keys_for(loader.circuit_id(), pk, vd)?;
+                COMPILED.fetch_add(1, Ordering::SeqCst);
+                rusk_profile::set_keys_target_features(&target_features())?;
+                info!(
+                    "{} Keys cache checking stage finished",
+                    loader.circuit_name()
+                );
+                Ok(())
+            }
+        }
+    }
+
+    // Number of circuits compiled at the same time. Every compilation holds
+    // large scratch buffers, so it is kept to one unless told otherwise.
+    fn compile_jobs() -> usize {
+        option_env!("RUSK_COMPILE_JOBS")
+            .map(|jobs| jobs.parse().expect("Bad RUSK_COMPILE_JOBS"))
+            .unwrap_or(1)
+            .max(1)
+    }
+
     fn check_keys_cache(
+        crs: &Crs,
         loader_list: &[&dyn CircuitLoader],
     ) -> Result<Vec<()>, Box<dyn std::error::Error>> {
-        loader_list
-            .iter()
-            .map(|loader| {
-                info!("{} Keys cache checking stage", loader.circuit_name());
-                match rusk_profile::keys_for(loader.circuit_id()) {
-                    Ok(_) => {
-                        info!(
-                            "{} already loaded correctly!",
-                            loader.circuit_name()
-                        );
-                        Ok(())
-                    }
-                    _ => {
-                        warn!("{} not cached!", loader.circuit_name());
-                        info!(
-                            "Compiling {} and adding to the cache",
-                            loader.circuit_name()
-                        );
-                        let (pk, vd) = loader.compile_circuit()?;
-                        rusk_profile::add_keys_for(
-                            loader.circuit_id(),
-                            pk,
-                            vd,
-                        )?;
-                        info!(
-                            "{} Keys cache checking stage finished",
-                            loader.circuit_name()
-                        );
-                        Ok(())
-                    }
+        let jobs = compile_jobs();
+        info!("Compiling up to {} circuit(s) at a time", jobs);
+
+        if jobs == 1 && !collect_errors() {
+            return loader_list
+                .iter()
+                .map(|loader| check_circuit_keys(crs, *loader))
+                .collect::<Result<Vec<()>, Box<dyn std::error::Error>>>();
+        }
+
+        let check = |loader: &&dyn CircuitLoader| {
+            check_circuit_keys(crs, *loader)
+                .map_err(|e| format!("{}: {}", loader.circuit_name(), e))
+        };
+        let results: Vec<Result<(), String>> = if jobs == 1 {
+            loader_list.iter().map(check).collect()
+        } else {
+            // The pool threads are the only ones compiling, which bounds
+            // the compilations running at once.
+            ThreadPoolBuilder::new()
+                .num_threads(jobs)
+                .thread_name(|i| format!("rusk-compile-{}", i))
+                .build()?
+                .install(|| loader_list.par_iter().map(check).collect())
+        };
+
+        let mut errors: Vec<String> =
+            results.into_iter().filter_map(Result::err).collect();
+        if !collect_errors() && !errors.is_empty() {
+            return Err(errors.remove(0).into());
+        }
+        errors.iter().for_each(|e| warn!("{}", e));
+
+        match errors.len() {
+            0 => Ok(vec![(); loader_list.len()]),
+            n => Err(format!(
+                "{} circuit(s) failed the keys check:\n{}",
+                n,
+                errors.join("\n")
+            )
+            .into()),
+        }
+    }
+
+    // Download the CRS served at `url`, resuming the download an earlier
+    // build left in `dev.crs.part`. The CRS is only returned once it passes
+    // the integrity check.
+    pub fn download_crs(
+        url: &str,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let mut path = rusk_profile::get_rusk_profile_dir()?;
+        path.push("dev.crs.part");
+
+        let mut part = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        let offset = part.metadata()?.len();
+
+        let complete = offset > 0
+            && rusk_profile::verify_common_reference_string(&std::fs::read(
+                &path,
+            )?);
+        if !complete {
+            info!("Downloading the CRS from {}, from byte {}", url, offset);
+            let response = match ureq::get(url)
+                .set("Range", &format!("bytes={}-", offset))
+                .call()
+            {
+                // Nothing is left past a corrupt part, start over next time.
+                Err(ureq::Error::Status(416, _)) => {
+                    std::fs::remove_file(&path)?;
+                    return Err("the partial CRS download is corrupt".into());
                 }
-            })
-            .collect::<Result<Vec<()>, Box<dyn std::error::Error>>>()
+                response => response?,
+            };
+
+            // A server ignoring the range sends the whole CRS again.
+            if response.status() != 206 {
+                part.set_len(0)?;
+            }
+            std::io::copy(&mut response.into_reader(), &mut part)?;
+        }
+
+        let crs = std::fs::read(&path)?;
+        std::fs::remove_file(&path)?;
+        if !rusk_profile::verify_common_reference_string(&crs) {
+            return Err("the downloaded CRS failed the integrity check".into());
+        }
+        Ok(crs)
+    }
+
+    // Load the keys bundle served at `url` into the profile, so only the
+    // circuits it lacks, or whose entry is corrupted, get compiled.
+    //
+    // The bundle's own entry hashes only catch corruption, so it is only
+    // trusted over https and once its digest matches `RUSK_KEYS_SHA256`.
+    fn warm_keys_cache(
+        url: &str,
+        loader_list: &[&dyn CircuitLoader],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if !url.starts_with("https://") {
+            return Err(format!("RUSK_KEYS_URL `{}` is not https", url).into());
+        }
+        let mut expected_hash = [0u8; 32];
+        match option_env!("RUSK_KEYS_SHA256") {
+            Some(hash) => hex::decode_to_slice(hash, &mut expected_hash)?,
+            None => return Err("RUSK_KEYS_SHA256 is not set".into()),
+        }
+
+        let missing: Vec<_> = loader_list
+            .iter()
+            .map(|loader| loader.circuit_id())
+            .filter(|id| rusk_profile::keys_for(id).is_err())
+            .cloned()
+            .collect();
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        info!("Downloading keys bundle from {}", url);
+        let mut bundle = vec![];
+        ureq::get(url)
+            .call()?
+            .into_reader()
+            .read_to_end(&mut bundle)?;
+
+        let added = rusk_profile::add_keys_from_bundle(
+            &bundle,
+            &expected_hash,
+            &missing,
+        )?;
+        info!(
+            "{} of {} missing circuit keys loaded from the bundle",
+            added.len(),
+            missing.len()
+        );
+        Ok(())
+    }
+
+    // Only keep the circuit named by `RUSK_KEYS_CIRCUIT`, if set, so a
+    // single circuit can be rebuilt while iterating on it.
+    fn select_circuits<'a>(
+        loader_list: &[&'a dyn CircuitLoader],
+    ) -> Result<Vec<&'a dyn CircuitLoader>, Box<dyn std::error::Error>> {
+        let name = match option_env!("RUSK_KEYS_CIRCUIT") {
+            Some(name) => name,
+            None => return Ok(loader_list.to_vec()),
+        };
+
+        match loader_list.iter().find(|l| l.circuit_name() == name) {
+            Some(loader) => Ok(vec![*loader]),
+            None => Err(format!(
+                "No circuit named {}, expected one of: {}",
+                name,
+                loader_list
+                    .iter()
+                    .map(|loader| loader.circuit_name())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+            .into()),
+        }
+    }
+
+    // When set, the size of every circuit is logged once its keys are known.
+    fn circuit_stats() -> bool {
+        option_env!("RUSK_CIRCUIT_STATS").unwrap_or("0") != "0"
+    }
+
+    // Log a table of the circuit sizes, so a blowup between versions shows up
+    // even when the keys weren't compiled.
+    //
+    // The gates are counted on a composer the gadget ran on, while the
+    // padded size and the public inputs are read back from the cached
+    // verifier data.
+    fn log_circuit_stats(
+        loader_list: &[&dyn CircuitLoader],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        info!(
+            "{:<24} {:>12} {:>12} {:>14}",
+            "circuit", "gates", "padded gates", "public inputs"
+        );
+        for loader in loader_list {
+            let vd = VerifierData::from_slice(
+                &rusk_profile::keys_for(loader.circuit_id())?.get_verifier()?,
+            )?;
+            info!(
+                "{:<24} {:>12} {:>12} {:>14}",
+                loader.circuit_name(),
+                loader.circuit_size()?.gates,
+                vd.key().padded_circuit_size(),
+                vd.pi_pos().len()
+            );
+        }
+        Ok(())
+    }
+
+    // Hold an advisory lock on the profile, so concurrent builds sharing it
+    // check the keys one after the other instead of interleaving writes.
+    fn lock_profile() -> Result<std::fs::File, Box<dyn std::error::Error>> {
+        let mut path = rusk_profile::get_rusk_profile_dir()?;
+        path.push("build.lock");
+
+        let file = std::fs::File::create(&path)?;
+        if file.try_lock_exclusive().is_err() {
+            info!("Waiting for another build to release {}", path.display());
+            file.lock_exclusive()?;
+        }
+        Ok(file)
+    }
+
+    // Write the table of the circuits with a transcript label of their own
+    // to `$OUT_DIR/transcript_labels.rs`, for `circuits.rs` to include.
+    fn write_transcript_labels(
+        loader_list: &[&dyn CircuitLoader],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut table = String::from("&[\n");
+        for loader in loader_list {
+            if let Some(label) = loader.transcript_label() {
+                table.push_str(&format!(
+                    "    ({:?}, &{:?}),\n",
+                    loader.circuit_id(),
+                    label
+                ));
+            }
+        }
+        table.push_str("]\n");
+
+        let mut path = std::path::PathBuf::from(std::env::var("OUT_DIR")?);
+        path.push("transcript_labels.rs");
+        std::fs::write(path, table)?;
+        Ok(())
+    }
+
+    // Write the table of the CRS degree every circuit needs to
+    // `$OUT_DIR/circuit_degrees.rs`, for `circuits.rs` to include.
+    fn write_circuit_degrees(
+        loader_list: &[&dyn CircuitLoader],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut table = String::from("&[\n");
+        for loader in loader_list {
+            table.push_str(&format!(
+                "    ({:?}, {}),\n",
+                loader.circuit_id(),
+                loader.circuit_size()?.padded
+            ));
+        }
+        table.push_str("]\n");
+
+        let mut path = std::path::PathBuf::from(std::env::var("OUT_DIR")?);
+        path.push("circuit_degrees.rs");
+        std::fs::write(path, table)?;
+        Ok(())
     }
 
     pub fn run_circuit_keys_checks(
+        pp: &PublicParameters,
         loader_list: Vec<&dyn CircuitLoader>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        clear_outdated_keys(&loader_list)?;
-        check_keys_cache(&loader_list).map(|_| ())
+        // Released when dropped, once the checks are over.
+        let _lock = if read_only() {
+            None
+        } else {
+            Some(lock_profile()?)
+        };
+
+        // The outdated keys are told apart from the full list, before any
+        // selection.
+        let all_loaders = loader_list;
+        let loader_list = select_circuits(&all_loaders)?;
+
+        if read_only() {
+            warn!("Read-only profile, outdated keys are left in place");
+        } else {
+            clear_outdated_keys(&all_loaders)?;
+
+            if let Some(url) = option_env!("RUSK_KEYS_URL") {
+                // Whatever can't be downloaded is compiled afterwards.
+                if let Err(e) = warm_keys_cache(url, &loader_list) {
+                    warn!("Failed loading the keys bundle: {}", e);
+                }
+            }
+        }
+        let started = Instant::now();
+        check_keys_cache(&Crs::new(pp)?, &loader_list)?;
+
+        // The labels are compiled into the node rather than kept in the
+        // profile, so it can't prove with one and verify with another.
+        write_transcript_labels(&all_loaders)?;
+        write_circuit_degrees(&all_loaders)?;
+
+        if !read_only() {
+            let summary = rusk_profile::BuildSummary {
+                circuits_compiled: COMPILED.load(Ordering::SeqCst),
+                compile_secs: started.elapsed().as_secs(),
+                crs_regenerated: CRS_REGENERATED.load(Ordering::SeqCst),
+                timestamp: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)?
+                    .as_secs(),
+            };
+            info!("{:?}", summary);
+            rusk_profile::set_build_summary(&summary)?;
+        }
+
+        if circuit_stats() {
+            log_circuit_stats(&loader_list)?;
+        }
+        Ok(())
     }
 }
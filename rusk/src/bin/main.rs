@@ -8,16 +8,23 @@
 mod unix;
 mod version;
 
-use clap::{App, Arg};
+use clap::{App, Arg, SubCommand};
 use futures::TryFutureExt;
+use rusk::services::admin::AdminServer;
 use rusk::services::blindbid::BlindBidServiceServer;
 use rusk::services::echoer::EchoerServer;
 use rusk::services::pki::KeysServer;
+use rusk::services::prover::ProverServer;
+use rusk::services::proving_key::ProvingKeyServiceServer;
+use rusk::services::verifier::VerifierServer;
 use rusk::Rusk;
 use rustc_tools_util::{get_version_info, VersionInfo};
+use std::collections::HashMap;
 use std::path::Path;
+use std::time::Duration;
 use tokio::net::UnixListener;
 use tonic::transport::Server;
+use tonic::{Request, Status};
 use version::show_version;
 
 /// Default UDS path that Rusk GRPC-server will connect to.
@@ -28,9 +35,66 @@ pub(crate) const PORT: &str = "8585";
 /// Default host_address that Rusk GRPC-server will listen to.
 pub(crate) const HOST_ADDRESS: &str = "127.0.0.1";
 
+/// Services accepting a `--rate-limit`.
+const RATE_LIMITED_SERVICES: [&str; 6] =
+    ["echoer", "blindbid", "keys", "verifier", "prover", "admin"];
+
+/// How the requests to the services are guarded.
+struct ServiceConfig<'a> {
+    api_token: Option<&'a str>,
+    proving_key_token: Option<&'a str>,
+    rate_limits: HashMap<&'a str, u32>,
+}
+
+type Interceptor =
+    Box<dyn Fn(Request<()>) -> Result<Request<()>, Status> + Send + Sync>;
+
+impl<'a> ServiceConfig<'a> {
+    /// Interceptor checking the API token and the rate limit of `service`,
+    /// if any of them is configured.
+    fn interceptor(&self, service: &str) -> Option<Interceptor> {
+        let auth = self
+            .api_token
+            .map(|token| rusk::auth::token_interceptor(token.to_string()));
+        let limit = self
+            .rate_limits
+            .get(service)
+            .map(|&rate| rusk::rate_limit::rate_limit_interceptor(rate));
+
+        if auth.is_none() && limit.is_none() {
+            return None;
+        }
+
+        Some(Box::new(move |request| {
+            let request = match &auth {
+                Some(auth) => auth(request)?,
+                None => request,
+            };
+            match &limit {
+                Some(limit) => limit(request),
+                None => Ok(request),
+            }
+        }))
+    }
+}
+
+/// Build the `$server` for `$rusk`, guarded as `$config` requires for
+/// `$service`.
+macro_rules! server {
+    ($server:ident, $rusk:expr, $config:expr, $service:expr) => {
+        match $config.interceptor($service) {
+            Some(interceptor) => $server::with_interceptor($rusk, interceptor),
+            None => $server::new($rusk),
+        }
+    };
+}
+
 #[tokio::main]
 async fn main() {
     let crate_info = get_version_info!();
+    let keepalive_interval = rusk::KEEPALIVE_INTERVAL.as_secs().to_string();
+    let keepalive_timeout = rusk::KEEPALIVE_TIMEOUT.as_secs().to_string();
+    let tcp_keepalive = rusk::TCP_KEEPALIVE.as_secs().to_string();
     let matches = App::new(&crate_info.crate_name)
         .version(show_version(crate_info).as_str())
         .author("Dusk Network B.V. All Rights Reserved.")
@@ -67,6 +131,69 @@ async fn main() {
                 .value_name("host")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("prover-threads")
+                .long("prover-threads")
+                .value_name("THREADS")
+                .help("Threads dedicated to proving, defaults to the number of CPUs minus one. Lower it when sharing the machine with a consensus node")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("proving-key-token")
+                .long("proving-key-token")
+                .value_name("TOKEN")
+                .help("Serve the cached prover keys to the clients presenting this token in their `authorization` metadata")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("api-token")
+                .long("api-token")
+                .value_name("TOKEN")
+                .help("Only accept the requests presenting this token in their `authorization` metadata. If unset, the services are open and the admin one isn't served")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("keepalive-interval")
+                .long("keepalive-interval")
+                .value_name("SECONDS")
+                .help("Interval between the HTTP/2 keepalive pings")
+                .default_value(&keepalive_interval)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("keepalive-timeout")
+                .long("keepalive-timeout")
+                .value_name("SECONDS")
+                .help("Time to wait for a keepalive ping acknowledgement before dropping the connection")
+                .default_value(&keepalive_timeout)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("tcp-keepalive")
+                .long("tcp-keepalive")
+                .value_name("SECONDS")
+                .help("Idle time before TCP probes the connection for a dead peer")
+                .default_value(&tcp_keepalive)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("rate-limit")
+                .long("rate-limit")
+                .value_name("SERVICE=RATE")
+                .help("Limit the requests per second a peer can send to a service, one of: echoer, blindbid, keys, verifier, prover, admin")
+                .multiple(true)
+                .number_of_values(1)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("max-proof-age")
+                .long("max-proof-age")
+                .value_name("CIRCUIT=INDEX:SECONDS")
+                .help("Refuse to verify the proofs of a circuit whose public input at INDEX, a Unix timestamp, is older than SECONDS")
+                .multiple(true)
+                .number_of_values(1)
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("log-level")
                 .long("log-level")
@@ -76,8 +203,101 @@ async fn main() {
                 .help("Output log level")
                 .takes_value(true),
         )
+        .subcommand(
+            SubCommand::with_name("keys")
+                .about("Inspect the circuit keys of the profile")
+                .subcommand(SubCommand::with_name("requirements").about(
+                    "Print the CRS degree required by every circuit",
+                ))
+                .subcommand(
+                    SubCommand::with_name("status")
+                        .about("Print what the last build did to the keys"),
+                )
+                .subcommand(
+                    SubCommand::with_name("compile")
+                        .about("Compile and cache the keys of the circuits missing from the profile")
+                        .arg(
+                            Arg::with_name("circuit")
+                                .long("circuit")
+                                .value_name("NAME")
+                                .help("Only compile the circuit registered as NAME, e.g. BlindBid")
+                                .takes_value(true),
+                        )
+                        .arg(
+                            Arg::with_name("force")
+                                .long("force")
+                                .help("Compile the keys even when they are cached already"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("bundle")
+                        .about("Export the cached keys as a bundle to serve through RUSK_KEYS_URL")
+                        .arg(
+                            Arg::with_name("output")
+                                .value_name("FILE")
+                                .help("Path the bundle is written to")
+                                .required(true)
+                                .takes_value(true),
+                        ),
+                ),
+        )
         .get_matches();
 
+    if let Some(keys) = matches.subcommand_matches("keys") {
+        if keys.subcommand_matches("requirements").is_some() {
+            for (name, degree) in rusk::circuits::circuit_requirements() {
+                println!("{:<24} {}", name, degree);
+            }
+        }
+        if keys.subcommand_matches("status").is_some() {
+            match rusk_profile::build_summary() {
+                Some(summary) => println!(
+                    "last warmed at {}: {} circuits in {}m{}s, CRS {}",
+                    summary.timestamp,
+                    summary.circuits_compiled,
+                    summary.compile_secs / 60,
+                    summary.compile_secs % 60,
+                    if summary.crs_regenerated {
+                        "regenerated"
+                    } else {
+                        "cached"
+                    }
+                ),
+                None => println!("no build summary recorded"),
+            }
+        }
+        if let Some(compile) = keys.subcommand_matches("compile") {
+            let force = compile.is_present("force");
+            let names = match compile.value_of("circuit") {
+                Some(name) => vec![name],
+                None => rusk::circuits::circuit_names(),
+            };
+            for name in names {
+                match rusk::circuits::compile_keys(name, force) {
+                    Ok(true) => println!("{:<24} compiled", name),
+                    Ok(false) => println!("{:<24} already cached", name),
+                    Err(e) => {
+                        eprintln!("{:<24} {}", name, e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+        }
+        if let Some(bundle) = keys.subcommand_matches("bundle") {
+            let output = bundle
+                .value_of("output")
+                .expect("Failed parsing the bundle path");
+            let bundle = rusk_profile::cached_circuit_ids()
+                .and_then(|ids| rusk_profile::keys_bundle(&ids))
+                .expect("Failed building the keys bundle");
+            std::fs::write(output, &bundle)
+                .expect("Failed writing the keys bundle");
+            // The digest to set as RUSK_KEYS_SHA256 where it is loaded.
+            println!("{}", hex::encode(rusk_profile::bundle_hash(&bundle)));
+        }
+        return;
+    }
+
     // Match tracing desired level.
     let log = match matches
         .value_of("log-level")
@@ -92,15 +312,90 @@ async fn main() {
     };
 
     // Generate a subscriber with the desired log level.
-    let subscriber = tracing_subscriber::fmt::Subscriber::builder()
-        .with_max_level(log)
-        .finish();
+    let subscriber =
+        tracing_subscriber::fmt::Subscriber::builder().with_max_level(log);
     // Set the subscriber as global.
     // so this subscriber will be used as the default in all threads for the
     // remainder of the duration of the program, similar to how `loggers`
     // work in the `log` crate.
-    tracing::subscriber::set_global_default(subscriber)
-        .expect("Failed on subscribe tracing");
+    let format = std::env::var("RUSK_LOG_FORMAT")
+        .unwrap_or_else(|_| "pretty".to_string());
+    match format.as_str() {
+        "json" => {
+            tracing::subscriber::set_global_default(subscriber.json().finish())
+        }
+        "pretty" => {
+            tracing::subscriber::set_global_default(subscriber.finish())
+        }
+        _ => panic!("Unknown RUSK_LOG_FORMAT `{}`", format),
+    }
+    .expect("Failed on subscribe tracing");
+
+    // Size the prover pool before any proof is requested.
+    if let Some(threads) = matches.value_of("prover-threads") {
+        let threads = threads
+            .parse::<usize>()
+            .expect("Failed parsing prover-threads arg");
+        rusk::prover_pool::set_threads(threads);
+    }
+
+    for policy in matches.values_of("max-proof-age").into_iter().flatten() {
+        let parse = || {
+            let mut parts = policy.splitn(2, '=');
+            let id = rusk::circuits::circuit_id(parts.next()?)?;
+            let mut parts = parts.next()?.splitn(2, ':');
+            let index = parts.next()?.parse::<usize>().ok()?;
+            let seconds = parts.next()?.parse::<u64>().ok()?;
+            Some((id, index, Duration::from_secs(seconds)))
+        };
+        let (id, index, max_age) =
+            parse().expect("Failed parsing max-proof-age arg");
+        rusk::services::verifier::set_max_proof_age(id, index, max_age);
+    }
+
+    let rate_limits = matches
+        .values_of("rate-limit")
+        .into_iter()
+        .flatten()
+        .map(|limit| {
+            let mut parts = limit.splitn(2, '=');
+            let service = parts.next().unwrap();
+            let rate = parts
+                .next()
+                .and_then(|rate| rate.parse::<u32>().ok())
+                .expect("Failed parsing rate-limit arg");
+            if !RATE_LIMITED_SERVICES.contains(&service) {
+                panic!("Unknown rate-limited service {}", service);
+            }
+            (service, rate)
+        })
+        .collect();
+
+    let config = ServiceConfig {
+        api_token: matches.value_of("api-token"),
+        proving_key_token: matches.value_of("proving-key-token"),
+        rate_limits,
+    };
+
+    // Keep the long-lived connections alive behind NATs and proxies, and
+    // reap the dead peers.
+    let seconds = |arg| {
+        Duration::from_secs(
+            matches
+                .value_of(arg)
+                .unwrap()
+                .parse::<u64>()
+                .unwrap_or_else(|_| panic!("Failed parsing {} arg", arg)),
+        )
+    };
+    let server = Server::builder()
+        .http2_keepalive_interval(Some(seconds("keepalive-interval")))
+        .http2_keepalive_timeout(Some(seconds("keepalive-timeout")))
+        .tcp_keepalive(Some(seconds("tcp-keepalive")));
+
+    // Reading the keys from disk shouldn't delay the server, so the report
+    // is logged while it starts.
+    tokio::task::spawn_blocking(rusk::circuits::log_keys_health);
 
     // Match the desired IPC method. Or set the default one depending on the OS
     // used. Then startup rusk with the final values.
@@ -108,8 +403,10 @@ async fn main() {
         Some(method) => match (cfg!(windows), method) {
             (_, "tcp_ip") => {
                 startup_with_tcp_ip(
+                    server,
                     matches.value_of("host").unwrap_or(HOST_ADDRESS),
                     matches.value_of("port").unwrap_or(PORT),
+                    &config,
                 )
                 .await
             }
@@ -118,7 +415,9 @@ async fn main() {
             }
             (false, "uds") => {
                 startup_with_uds(
+                    server,
                     matches.value_of("socket").unwrap_or(SOCKET_PATH),
+                    &config,
                 )
                 .await
             }
@@ -127,13 +426,17 @@ async fn main() {
         None => {
             if cfg!(windows) {
                 startup_with_tcp_ip(
+                    server,
                     matches.value_of("host").unwrap_or(HOST_ADDRESS),
                     matches.value_of("port").unwrap_or(PORT),
+                    &config,
                 )
                 .await
             } else {
                 startup_with_uds(
+                    server,
                     matches.value_of("socket").unwrap_or(SOCKET_PATH),
+                    &config,
                 )
                 .await
             }
@@ -147,7 +450,9 @@ async fn main() {
 
 #[cfg(not(target_os = "windows"))]
 async fn startup_with_uds(
+    mut server: Server,
     path: &str,
+    config: &ServiceConfig<'_>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     tokio::fs::create_dir_all(Path::new(path).parent().unwrap()).await?;
 
@@ -155,9 +460,13 @@ async fn startup_with_uds(
 
     let rusk = Rusk::default();
 
-    let echoer = EchoerServer::new(rusk);
-    let blindbid = BlindBidServiceServer::new(rusk);
-    let keys = KeysServer::new(rusk);
+    let echoer = server!(EchoerServer, rusk, config, "echoer");
+    let blindbid = server!(BlindBidServiceServer, rusk, config, "blindbid");
+    let keys = server!(KeysServer, rusk, config, "keys");
+    let verifier = server!(VerifierServer, rusk, config, "verifier");
+    let prover = server!(ProverServer, rusk, config, "prover");
+    let admin = admin_server(rusk, config);
+    let proving_key = proving_key_server(rusk, config.proving_key_token);
 
     let incoming = {
         async_stream::stream! {
@@ -167,10 +476,14 @@ async fn startup_with_uds(
         }
     };
 
-    Server::builder()
+    server
         .add_service(echoer)
         .add_service(blindbid)
         .add_service(keys)
+        .add_service(verifier)
+        .add_service(prover)
+        .add_optional_service(admin)
+        .add_optional_service(proving_key)
         .serve_with_incoming(incoming)
         .await?;
 
@@ -178,8 +491,10 @@ async fn startup_with_uds(
 }
 
 async fn startup_with_tcp_ip(
+    mut server: Server,
     host: &str,
     port: &str,
+    config: &ServiceConfig<'_>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut full_address = host.to_string();
     full_address.push(':');
@@ -187,15 +502,48 @@ async fn startup_with_tcp_ip(
     let addr: std::net::SocketAddr = full_address.parse()?;
     let rusk = Rusk::default();
 
-    let echoer = EchoerServer::new(rusk);
-    let blindbid = BlindBidServiceServer::new(rusk);
-    let keys = KeysServer::new(rusk);
+    let echoer = server!(EchoerServer, rusk, config, "echoer");
+    let blindbid = server!(BlindBidServiceServer, rusk, config, "blindbid");
+    let keys = server!(KeysServer, rusk, config, "keys");
+    let verifier = server!(VerifierServer, rusk, config, "verifier");
+    let prover = server!(ProverServer, rusk, config, "prover");
+    let admin = admin_server(rusk, config);
+    let proving_key = proving_key_server(rusk, config.proving_key_token);
 
     // Build the Server with the `Echo` service attached to it.
-    Ok(Server::builder()
+    Ok(server
         .add_service(echoer)
         .add_service(blindbid)
         .add_service(keys)
+        .add_service(verifier)
+        .add_service(prover)
+        .add_optional_service(admin)
+        .add_optional_service(proving_key)
         .serve(addr)
         .await?)
 }
+
+/// The `Admin` service is only served when an API token is configured, so
+/// there is no way to reach it without one.
+fn admin_server(
+    rusk: Rusk,
+    config: &ServiceConfig<'_>,
+) -> Option<AdminServer<Rusk>> {
+    config
+        .api_token
+        .map(|_| server!(AdminServer, rusk, config, "admin"))
+}
+
+/// The `ProvingKeyService` is only served when a token is configured, and
+/// then only to the clients presenting it.
+fn proving_key_server(
+    rusk: Rusk,
+    token: Option<&str>,
+) -> Option<ProvingKeyServiceServer<Rusk>> {
+    token.map(|token| {
+        ProvingKeyServiceServer::with_interceptor(
+            rusk,
+            rusk::auth::token_interceptor(token.to_string()),
+        )
+    })
+}
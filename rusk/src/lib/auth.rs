@@ -0,0 +1,37 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Shared-secret authentication for the Rusk services.
+
+use subtle::ConstantTimeEq;
+use tonic::{Request, Status};
+
+/// Metadata key carrying the token of a request.
+pub const AUTHORIZATION: &str = "authorization";
+
+/// Build an interceptor rejecting every request whose `authorization`
+/// metadata doesn't match `token`.
+///
+/// The comparison is done in constant time, so the token can't be guessed
+/// byte by byte from the response times.
+pub fn token_interceptor(
+    token: String,
+) -> impl Fn(Request<()>) -> Result<Request<()>, Status> + Send + Sync + 'static
+{
+    move |request: Request<()>| {
+        let authorized = request
+            .metadata()
+            .get(AUTHORIZATION)
+            .map(|value| value.as_bytes().ct_eq(token.as_bytes()).into())
+            .unwrap_or(false);
+
+        if authorized {
+            Ok(request)
+        } else {
+            Err(Status::unauthenticated("Invalid or missing token"))
+        }
+    }
+}
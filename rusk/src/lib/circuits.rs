@@ -0,0 +1,267 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Circuits known by Rusk and the state of their keys in the profile.
+
+mod dummy;
+
+use bid_circuits::BidCorrectnessCircuit;
+use blindbid_circuits::BlindBidCircuit;
+use dusk_plonk::prelude::*;
+use dusk_poseidon::tree::PoseidonBranch;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io;
+use std::sync::Mutex;
+use tonic::Status;
+use tracing::{info, warn};
+use transfer_circuits::*;
+
+/// Name and circuit id of every circuit whose keys the node needs.
+const CIRCUITS: [(&str, [u8; 32]); 17] = [
+    ("BidCorrectness", BidCorrectnessCircuit::CIRCUIT_ID),
+    ("BlindBid", BlindBidCircuit::CIRCUIT_ID),
+    ("STCT", SendToContractTransparentCircuit::CIRCUIT_ID),
+    ("STCO", SendToContractObfuscatedCircuit::CIRCUIT_ID),
+    ("WFO", WithdrawFromObfuscatedCircuit::CIRCUIT_ID),
+    ("ExecuteOneZero", ExecuteCircuitOneZero::CIRCUIT_ID),
+    ("ExecuteOneOne", ExecuteCircuitOneOne::CIRCUIT_ID),
+    ("ExecuteOneTwo", ExecuteCircuitOneTwo::CIRCUIT_ID),
+    ("ExecuteTwoZero", ExecuteCircuitTwoZero::CIRCUIT_ID),
+    ("ExecuteTwoOne", ExecuteCircuitTwoOne::CIRCUIT_ID),
+    ("ExecuteTwoTwo", ExecuteCircuitTwoTwo::CIRCUIT_ID),
+    ("ExecuteThreeZero", ExecuteCircuitThreeZero::CIRCUIT_ID),
+    ("ExecuteThreeOne", ExecuteCircuitThreeOne::CIRCUIT_ID),
+    ("ExecuteThreeTwo", ExecuteCircuitThreeTwo::CIRCUIT_ID),
+    ("ExecuteFourZero", ExecuteCircuitFourZero::CIRCUIT_ID),
+    ("ExecuteFourOne", ExecuteCircuitFourOne::CIRCUIT_ID),
+    ("ExecuteFourTwo", ExecuteCircuitFourTwo::CIRCUIT_ID),
+];
+
+/// Name every circuit is registered as.
+pub fn circuit_names() -> Vec<&'static str> {
+    CIRCUITS.iter().map(|(name, _)| *name).collect()
+}
+
+/// Circuit id of the circuit registered as `name`, e.g. `STCT`.
+pub fn circuit_id(name: &str) -> Option<[u8; 32]> {
+    CIRCUITS
+        .iter()
+        .find(|(circuit, _)| *circuit == name)
+        .map(|(_, id)| *id)
+}
+
+/// Circuit id of every `Execute` variant, along with its number of inputs
+/// and outputs.
+const EXECUTE_SHAPES: [([u8; 32], usize, usize); 12] = [
+    (ExecuteCircuitOneZero::CIRCUIT_ID, 1, 0),
+    (ExecuteCircuitOneOne::CIRCUIT_ID, 1, 1),
+    (ExecuteCircuitOneTwo::CIRCUIT_ID, 1, 2),
+    (ExecuteCircuitTwoZero::CIRCUIT_ID, 2, 0),
+    (ExecuteCircuitTwoOne::CIRCUIT_ID, 2, 1),
+    (ExecuteCircuitTwoTwo::CIRCUIT_ID, 2, 2),
+    (ExecuteCircuitThreeZero::CIRCUIT_ID, 3, 0),
+    (ExecuteCircuitThreeOne::CIRCUIT_ID, 3, 1),
+    (ExecuteCircuitThreeTwo::CIRCUIT_ID, 3, 2),
+    (ExecuteCircuitFourZero::CIRCUIT_ID, 4, 0),
+    (ExecuteCircuitFourOne::CIRCUIT_ID, 4, 1),
+    (ExecuteCircuitFourTwo::CIRCUIT_ID, 4, 2),
+];
+
+/// `(inputs, outputs)` combinations of the `Execute` circuits whose keys are
+/// cached, i.e. the transaction shapes the node can prove and verify.
+pub fn cached_execute_shapes() -> Result<Vec<(usize, usize)>, io::Error> {
+    let cached = rusk_profile::cached_circuit_ids()?;
+
+    Ok(EXECUTE_SHAPES
+        .iter()
+        .filter(|(id, _, _)| cached.contains(id))
+        .map(|&(_, inputs, outputs)| (inputs, outputs))
+        .collect())
+}
+
+/// Circuit id and label of the circuits whose loader gives them a transcript
+/// label of their own, as written by the build script.
+const CIRCUIT_LABELS: &[([u8; 32], &[u8])] =
+    include!(concat!(env!("OUT_DIR"), "/transcript_labels.rs"));
+
+lazy_static! {
+    static ref TRANSCRIPT_LABELS: Mutex<HashMap<[u8; 32], &'static [u8]>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Label to initialize the transcripts of the circuit `id` with.
+///
+/// It is the [`TRANSCRIPT_LABEL`](crate::TRANSCRIPT_LABEL), followed by the
+/// label of the circuit when its loader gives it one of its own.
+pub fn transcript_label(id: &[u8; 32]) -> &'static [u8] {
+    let label = match CIRCUIT_LABELS.iter().find(|(circuit, _)| circuit == id) {
+        Some((_, label)) => label,
+        None => return *crate::TRANSCRIPT_LABEL,
+    };
+
+    let mut labels = TRANSCRIPT_LABELS
+        .lock()
+        .expect("Transcript labels poisoned");
+    *labels.entry(*id).or_insert_with(|| {
+        let mut full = crate::TRANSCRIPT_LABEL.to_vec();
+        full.push(b'-');
+        full.extend_from_slice(label);
+        Box::leak(full.into_boxed_slice())
+    })
+}
+
+/// Verifier data of the cached circuits whose transcripts don't use the
+/// shared [`TRANSCRIPT_LABEL`](crate::TRANSCRIPT_LABEL), along with their
+/// label.
+pub(crate) fn labelled_verifier_data() -> Vec<(Vec<u8>, &'static [u8])> {
+    CIRCUIT_LABELS
+        .iter()
+        .filter_map(|(id, _)| {
+            let vd = rusk_profile::keys_for(id)
+                .and_then(|keys| keys.get_verifier())
+                .ok()?;
+            Some((vd, transcript_label(id)))
+        })
+        .collect()
+}
+
+/// Label to verify the proofs checked against `verifier_data` with, the
+/// same the [`host_module`](crate::host_module) picks for them.
+pub(crate) fn transcript_label_for_verifier_data(
+    verifier_data: &[u8],
+) -> &'static [u8] {
+    labelled_verifier_data()
+        .into_iter()
+        .find(|(vd, _)| vd.as_slice() == verifier_data)
+        .map_or(*crate::TRANSCRIPT_LABEL, |(_, label)| label)
+}
+
+/// Keys cached for the circuit `id`.
+///
+/// A node lacking the keys of some circuits still serves the others, so the
+/// requests needing missing keys are answered with `Unimplemented`.
+pub fn cached_keys(id: &[u8; 32]) -> Result<rusk_profile::Keys, Status> {
+    rusk_profile::keys_for(id).map_err(|_| {
+        let name = CIRCUITS
+            .iter()
+            .find(|(_, circuit)| circuit == id)
+            .map(|(name, _)| name.to_string())
+            .unwrap_or_else(|| hex::encode(id));
+        warn!("Request for the {} circuit, whose keys are missing", name);
+        Status::unimplemented(format!("No keys cached for circuit {}", name))
+    })
+}
+
+/// Circuit id and padded circuit size of every circuit, as written by the
+/// build script.
+const CIRCUIT_DEGREES: &[([u8; 32], usize)] =
+    include!(concat!(env!("OUT_DIR"), "/circuit_degrees.rs"));
+
+/// Name of every circuit along with the CRS degree its keys are compiled
+/// for, whether they are cached or not.
+///
+/// A CRS covers the circuits whose degree is at most its own.
+pub fn circuit_requirements() -> Vec<(&'static str, usize)> {
+    CIRCUITS
+        .iter()
+        .filter_map(|(name, id)| {
+            CIRCUIT_DEGREES
+                .iter()
+                .find(|(circuit, _)| circuit == id)
+                .map(|(_, degree)| (*name, *degree))
+        })
+        .collect()
+}
+
+/// Compile the keys of the circuit registered as `name` and cache them,
+/// unless they are cached already and `force` isn't set.
+///
+/// The circuit is filled as the build script fills it, so the keys are the
+/// ones a build would cache. Returns whether they were compiled.
+pub fn compile_keys(name: &str, force: bool) -> anyhow::Result<bool> {
+    let id = circuit_id(name).ok_or_else(|| {
+        anyhow::anyhow!(
+            "No circuit named {}, expected one of: {}",
+            name,
+            circuit_names().join(", ")
+        )
+    })?;
+    if !force && rusk_profile::keys_for(&id).is_ok() {
+        return Ok(false);
+    }
+
+    macro_rules! execute {
+        ($circuit:ident, $inputs:expr, $outputs:expr) => {
+            compile(&mut $circuit::try_from(dummy::execute(
+                $inputs, $outputs,
+            )?)?)
+        };
+    }
+
+    let branch = PoseidonBranch::<17>::default();
+    let (pk, vd) = match name {
+        "BidCorrectness" => compile(&mut dummy::bid()),
+        "BlindBid" => compile(&mut dummy::blindbid(&branch)),
+        "STCT" => compile(&mut dummy::stct()),
+        "STCO" => compile(&mut dummy::stco()),
+        "WFO" => compile(&mut dummy::wfo()),
+        "ExecuteOneZero" => execute!(ExecuteCircuitOneZero, 1, 0),
+        "ExecuteOneOne" => execute!(ExecuteCircuitOneOne, 1, 1),
+        "ExecuteOneTwo" => execute!(ExecuteCircuitOneTwo, 1, 2),
+        "ExecuteTwoZero" => execute!(ExecuteCircuitTwoZero, 2, 0),
+        "ExecuteTwoOne" => execute!(ExecuteCircuitTwoOne, 2, 1),
+        "ExecuteTwoTwo" => execute!(ExecuteCircuitTwoTwo, 2, 2),
+        "ExecuteThreeZero" => execute!(ExecuteCircuitThreeZero, 3, 0),
+        "ExecuteThreeOne" => execute!(ExecuteCircuitThreeOne, 3, 1),
+        "ExecuteThreeTwo" => execute!(ExecuteCircuitThreeTwo, 3, 2),
+        "ExecuteFourZero" => execute!(ExecuteCircuitFourZero, 4, 0),
+        "ExecuteFourOne" => execute!(ExecuteCircuitFourOne, 4, 1),
+        "ExecuteFourTwo" => execute!(ExecuteCircuitFourTwo, 4, 2),
+        _ => unreachable!("{} is registered without a dummy circuit", name),
+    }?;
+
+    rusk_profile::add_keys_for(&id, pk, vd)?;
+    rusk_profile::set_keys_target_features(env!("RUSK_TARGET_FEATURES"))?;
+    Ok(true)
+}
+
+// Compile `circuit` with the node's CRS into its prover key and verifier
+// data bytes.
+fn compile<C: Circuit>(circuit: &mut C) -> anyhow::Result<(Vec<u8>, Vec<u8>)> {
+    let (pk, vd) = circuit
+        .compile(&crate::PUB_PARAMS)
+        .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+    Ok((pk.to_var_bytes(), vd.to_var_bytes()))
+}
+
+/// Log, once per circuit, whether its keys are cached along with the hash of
+/// their verifier data.
+///
+/// A warning is also logged when the keys were compiled by a build for other
+/// target features than the node's.
+///
+/// Meant to run at startup, so a bad deploy shows before the first proof is
+/// requested. It reads every verifier data from disk, so run it off the async
+/// runtime.
+pub fn log_keys_health() {
+    let features = env!("RUSK_TARGET_FEATURES");
+    match rusk_profile::keys_target_features() {
+        Some(keys) if keys != features => warn!(
+            "Keys compiled for target features {}, the node is built for {}",
+            keys, features
+        ),
+        _ => info!("Target features: {}", features),
+    }
+
+    for (name, id) in CIRCUITS.iter() {
+        match rusk_profile::verifier_data_hash(id) {
+            Ok(hash) => info!("{} keys cached: {}", name, hex::encode(hash)),
+            Err(e) => warn!("{} keys missing: {}", name, e),
+        }
+    }
+}
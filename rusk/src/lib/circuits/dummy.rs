@@ -0,0 +1,226 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Circuits filled with dummy inputs, to compile their keys from.
+//!
+//! The build script includes this module too, so the keys it caches and the
+//! ones `rusk keys compile` caches come from the very same circuits.
+
+use bid_circuits::BidCorrectnessCircuit;
+use blindbid_circuits::BlindBidCircuit;
+use dusk_blindbid::{Bid, Score};
+use dusk_bls12_381::BlsScalar;
+use dusk_jubjub::{
+    JubJubAffine, JubJubScalar, GENERATOR_EXTENDED, GENERATOR_NUMS_EXTENDED,
+};
+use dusk_pki::{PublicSpendKey, SecretSpendKey};
+use dusk_poseidon::tree::PoseidonBranch;
+use phoenix_core::{Message, Note};
+use std::convert::TryInto;
+use transfer_circuits::{
+    Error as TransferError, ExecuteCircuit, SendToContractObfuscatedCircuit,
+    SendToContractTransparentCircuit, WithdrawFromObfuscatedCircuit,
+};
+
+/// BidCorrectness circuit of a Bid commitment.
+pub fn bid() -> BidCorrectnessCircuit {
+    let value = JubJubScalar::from(100000_u64);
+    let blinder = JubJubScalar::from(50000_u64);
+
+    let c = JubJubAffine::from(
+        (GENERATOR_EXTENDED * value) + (GENERATOR_NUMS_EXTENDED * blinder),
+    );
+
+    BidCorrectnessCircuit {
+        commitment: c,
+        value,
+        blinder,
+    }
+}
+
+/// BlindBid circuit of a correct Bid, opening in the Bid tree `branch`.
+pub fn blindbid(branch: &PoseidonBranch<17>) -> BlindBidCircuit<'_> {
+    // Generate a correct Bid
+    let secret = JubJubScalar::random(&mut rand::thread_rng());
+    let secret_k = BlsScalar::random(&mut rand::thread_rng());
+    let bid = random_bid(&secret, secret_k);
+    let secret: JubJubAffine = (GENERATOR_EXTENDED * secret).into();
+
+    // Generate fields for the Bid & required by the compute_score
+    let consensus_round_seed = 50u64;
+    let latest_consensus_round = 50u64;
+    let latest_consensus_step = 50u64;
+
+    // Generate a `Score` for our Bid with the consensus parameters
+    let score = Score::compute(
+        &bid,
+        &secret,
+        secret_k,
+        *branch.root(),
+        BlsScalar::from(consensus_round_seed),
+        latest_consensus_round,
+        latest_consensus_step,
+    )
+    .expect("Score gen error");
+
+    BlindBidCircuit {
+        bid,
+        score,
+        secret_k,
+        secret,
+        seed: BlsScalar::from(consensus_round_seed),
+        latest_consensus_round: BlsScalar::from(latest_consensus_round),
+        latest_consensus_step: BlsScalar::from(latest_consensus_step),
+        branch,
+    }
+}
+
+fn random_bid(secret: &JubJubScalar, secret_k: BlsScalar) -> Bid {
+    let mut rng = rand::thread_rng();
+    let pk_r = PublicSpendKey::from(SecretSpendKey::random(&mut rng));
+    let stealth_addr = pk_r.gen_stealth_address(&secret);
+    let secret = GENERATOR_EXTENDED * secret;
+    let value = 60_000u64;
+    let value = JubJubScalar::from(value);
+    // Set the timestamps as the max values so the proofs do not fail for
+    // them (never expired or non-elegible).
+    let elegibility_ts = u64::MAX;
+    let expiration_ts = u64::MAX;
+
+    Bid::new(
+        &mut rng,
+        &stealth_addr,
+        &value,
+        &secret.into(),
+        secret_k,
+        elegibility_ts,
+        expiration_ts,
+    )
+    .expect("Error generating a Bid")
+}
+
+/// STCT circuit of a crossover sent to a random contract.
+pub fn stct() -> SendToContractTransparentCircuit {
+    let rng = &mut rand::thread_rng();
+
+    let c_ssk = SecretSpendKey::random(rng);
+    let c_vk = c_ssk.view_key();
+    let c_psk = c_ssk.public_spend_key();
+
+    let c_address = BlsScalar::random(rng);
+
+    let c_value = 100;
+    let c_blinding_factor = JubJubScalar::random(rng);
+
+    let c_note = Note::obfuscated(rng, &c_psk, c_value, c_blinding_factor);
+    let (mut fee, crossover) = c_note
+        .try_into()
+        .expect("Failed to convert note into fee/crossover pair!");
+
+    fee.gas_limit = 5;
+    fee.gas_price = 1;
+
+    let c_signature = SendToContractTransparentCircuit::sign(
+        rng, &c_ssk, &fee, &crossover, c_value, &c_address,
+    );
+
+    SendToContractTransparentCircuit::new(
+        fee,
+        crossover,
+        &c_vk,
+        c_address,
+        c_signature,
+    )
+    .expect("Failed to create STCT circuit!")
+}
+
+/// STCO circuit of a crossover sent to a random contract.
+pub fn stco() -> SendToContractObfuscatedCircuit {
+    let rng = &mut rand::thread_rng();
+
+    let ssk = SecretSpendKey::random(rng);
+    let vk = ssk.view_key();
+    let psk = ssk.public_spend_key();
+
+    let c_address = BlsScalar::random(rng);
+
+    let c_value = 100;
+    let c_blinding_factor = JubJubScalar::random(rng);
+    let c_note = Note::obfuscated(rng, &psk, c_value, c_blinding_factor);
+    let (mut fee, crossover) = c_note
+        .try_into()
+        .expect("Failed to convert note into fee/crossover pair!");
+
+    fee.gas_limit = 5;
+    fee.gas_price = 1;
+
+    let message_r = JubJubScalar::random(rng);
+    let message_value = 100;
+    let message = Message::new(rng, &message_r, &psk, message_value);
+
+    let c_signature = SendToContractObfuscatedCircuit::sign(
+        rng, &ssk, &fee, &crossover, &message, &c_address,
+    );
+
+    SendToContractObfuscatedCircuit::new(
+        fee,
+        crossover,
+        &vk,
+        c_signature,
+        true,
+        message,
+        &psk,
+        message_r,
+        c_address,
+    )
+    .expect("Failed to generate circuit!")
+}
+
+/// WFO circuit of an obfuscated note split into a change and an output.
+pub fn wfo() -> WithdrawFromObfuscatedCircuit {
+    let rng = &mut rand::thread_rng();
+
+    let i_ssk = SecretSpendKey::random(rng);
+    let i_vk = i_ssk.view_key();
+    let i_psk = i_ssk.public_spend_key();
+    let i_value = 100;
+    let i_blinding_factor = JubJubScalar::random(rng);
+    let i_note = Note::obfuscated(rng, &i_psk, i_value, i_blinding_factor);
+
+    let c_ssk = SecretSpendKey::random(rng);
+    let c_psk = c_ssk.public_spend_key();
+    let c_r = JubJubScalar::random(rng);
+    let c_value = 25;
+    let c = Message::new(rng, &c_r, &c_psk, c_value);
+
+    let o_ssk = SecretSpendKey::random(rng);
+    let o_vk = o_ssk.view_key();
+    let o_psk = o_ssk.public_spend_key();
+    let o_value = 75;
+    let o_blinding_factor = JubJubScalar::random(rng);
+    let o_note = Note::obfuscated(rng, &o_psk, o_value, o_blinding_factor);
+
+    WithdrawFromObfuscatedCircuit::new(
+        &i_note,
+        Some(&i_vk),
+        &c,
+        c_r,
+        &c_psk,
+        &o_note,
+        Some(&o_vk),
+    )
+    .expect("Failed to generate circuit!")
+}
+
+/// Execute circuit of `inputs` notes spent into `outputs` ones.
+pub fn execute(
+    inputs: usize,
+    outputs: usize,
+) -> Result<ExecuteCircuit, TransferError> {
+    let rng = &mut rand::thread_rng();
+
+    ExecuteCircuit::create_dummy_circuit(rng, inputs, outputs, true)
+}
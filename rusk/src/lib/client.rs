@@ -0,0 +1,85 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Connection setup shared by the clients of the Rusk services.
+
+use crate::services::echoer::EchoerClient;
+use std::time::Duration;
+use tonic::transport::{Channel, ClientTlsConfig, Error, Uri};
+
+/// Builder of the channels the generated clients are made from.
+///
+/// The keepalives default to the ones the Rusk server uses, so idle
+/// connections survive on both ends.
+#[derive(Debug, Clone)]
+pub struct ClientBuilder {
+    uri: Uri,
+    tls: Option<ClientTlsConfig>,
+    timeout: Option<Duration>,
+    keepalive_interval: Duration,
+    keepalive_timeout: Duration,
+    tcp_keepalive: Option<Duration>,
+}
+
+impl ClientBuilder {
+    pub fn new(uri: Uri) -> Self {
+        Self {
+            uri,
+            tls: None,
+            timeout: None,
+            keepalive_interval: crate::KEEPALIVE_INTERVAL,
+            keepalive_timeout: crate::KEEPALIVE_TIMEOUT,
+            tcp_keepalive: Some(crate::TCP_KEEPALIVE),
+        }
+    }
+
+    /// Connect over TLS, e.g. to a server behind a TLS terminating proxy.
+    pub fn tls(mut self, tls: ClientTlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Fail the requests that take longer than `timeout`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Ping the server every `interval`, dropping the connection when a
+    /// ping isn't acknowledged within `timeout`.
+    pub fn keepalive(mut self, interval: Duration, timeout: Duration) -> Self {
+        self.keepalive_interval = interval;
+        self.keepalive_timeout = timeout;
+        self
+    }
+
+    /// TCP keepalive of the connection, `None` to disable it.
+    pub fn tcp_keepalive(mut self, keepalive: Option<Duration>) -> Self {
+        self.tcp_keepalive = keepalive;
+        self
+    }
+
+    /// Connect to the server, returning a channel any client can use.
+    pub async fn connect(&self) -> Result<Channel, Error> {
+        let mut endpoint = Channel::builder(self.uri.clone())
+            .http2_keep_alive_interval(self.keepalive_interval)
+            .keep_alive_timeout(self.keepalive_timeout)
+            .tcp_keepalive(self.tcp_keepalive);
+
+        if let Some(timeout) = self.timeout {
+            endpoint = endpoint.timeout(timeout);
+        }
+        if let Some(tls) = self.tls.clone() {
+            endpoint = endpoint.tls_config(tls)?;
+        }
+
+        endpoint.connect().await
+    }
+
+    pub async fn echoer(&self) -> Result<EchoerClient<Channel>, Error> {
+        Ok(EchoerClient::new(self.connect().await?))
+    }
+}
@@ -4,13 +4,28 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use std::time::Duration;
 use tracing::info;
+pub mod auth;
+pub mod circuits;
+#[cfg(feature = "client")]
+pub mod client;
 pub mod encoding;
+pub mod prover_pool;
+pub mod rate_limit;
 pub mod services;
 pub mod transaction;
 
 pub use rusk_vm as vm;
 
+/// Default interval between the HTTP/2 keepalive pings sent to the peer.
+pub const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
+/// Default time a keepalive ping has to be acknowledged before the
+/// connection is closed.
+pub const KEEPALIVE_TIMEOUT: Duration = Duration::from_secs(20);
+/// Default idleness before TCP starts probing a connection.
+pub const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+
 #[derive(Debug, Copy, Clone)]
 pub struct Rusk {}
 
@@ -22,10 +37,31 @@ impl Default for Rusk {
         info!("Loading CRS...");
         lazy_static::initialize(&PUB_PARAMS);
         info!("CRS was successfully loaded...");
+        info!(
+            "Using transcript label {}",
+            String::from_utf8_lossy(*TRANSCRIPT_LABEL)
+        );
         Rusk {}
     }
 }
 
+/// Re-read the CRS from the profile and check its integrity, e.g. after a
+/// suspected disk fault.
+pub fn verify_crs() -> Result<bool, std::io::Error> {
+    let crs = rusk_profile::get_common_reference_string()?;
+    Ok(rusk_profile::verify_common_reference_string(&crs))
+}
+
+/// Host module the contracts run by the node verify their proofs with, set
+/// to the [`TRANSCRIPT_LABEL`] the node proves with and to the labels of the
+/// circuits cached with one of their own.
+pub fn host_module() -> rusk_abi::RuskModule {
+    circuits::labelled_verifier_data().into_iter().fold(
+        rusk_abi::RuskModule::with_label(&PUB_PARAMS, *TRANSCRIPT_LABEL),
+        |module, (vd, label)| module.with_circuit_label(vd, label),
+    )
+}
+
 use dusk_plonk::prelude::PublicParameters;
 use lazy_static::lazy_static;
 lazy_static! {
@@ -34,4 +70,18 @@ lazy_static! {
 
         PublicParameters::from_slice_unchecked(pp.as_slice())
     };
+
+    /// Label initializing the transcript of every proof the node generates
+    /// or verifies.
+    ///
+    /// It is `dusk-network`, or `dusk-network-<id>` when built with
+    /// `RUSK_NETWORK_ID` set, so the proofs of a fork or a test network
+    /// never verify on another one.
+    pub static ref TRANSCRIPT_LABEL: &'static [u8] =
+        match option_env!("RUSK_NETWORK_ID") {
+            Some(id) => Box::leak(
+                format!("dusk-network-{}", id).into_bytes().into_boxed_slice(),
+            ),
+            None => b"dusk-network",
+        };
 }
@@ -0,0 +1,166 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Dedicated thread pool where the proofs requested to Rusk are generated.
+//!
+//! Proving is CPU bound and can take seconds, so running it on the async
+//! runtime would starve the tonic reactor. Handlers hand their proving work
+//! to this pool and await the result instead.
+//!
+//! The pool size defaults to the number of CPUs minus one, leaving a core to
+//! the runtime. Operators co-locating the prover with a consensus node
+//! should lower it with [`set_threads`] (`--prover-threads` in the binary)
+//! before the first proof is requested.
+//!
+//! Tasks are queued by [`Priority`]. Whenever a thread is free it runs the
+//! oldest high priority task, if any, so the node's own consensus proofs
+//! aren't held back by the external requests. A running task is never
+//! interrupted.
+
+use lazy_static::lazy_static;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use tokio::sync::oneshot;
+use tonic::Status;
+use tracing::{error, info};
+
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Priority of a proving task.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Priority {
+    /// Proofs the node needs itself, such as the consensus ones.
+    High,
+    /// Proofs requested through the services.
+    Low,
+}
+
+#[derive(Default)]
+struct Queues {
+    high: VecDeque<Job>,
+    low: VecDeque<Job>,
+}
+
+impl Queues {
+    fn push(&mut self, priority: Priority, job: Job) {
+        match priority {
+            Priority::High => self.high.push_back(job),
+            Priority::Low => self.low.push_back(job),
+        }
+    }
+
+    fn pop(&mut self) -> Option<Job> {
+        self.high.pop_front().or_else(|| self.low.pop_front())
+    }
+}
+
+lazy_static! {
+    static ref POOL: ThreadPool = {
+        let threads = match THREADS.load(Ordering::SeqCst) {
+            0 => default_threads(),
+            n => n,
+        };
+        info!("Starting the prover pool with {} threads", threads);
+
+        ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|i| format!("rusk-prover-{}", i))
+            .panic_handler(|_| error!("A proving task panicked"))
+            .build()
+            .expect("Failed to build the prover thread pool")
+    };
+    static ref QUEUES: Mutex<Queues> = Mutex::new(Queues::default());
+}
+
+/// Number of threads used when none is configured.
+pub fn default_threads() -> usize {
+    num_cpus::get().saturating_sub(1).max(1)
+}
+
+/// Set the number of threads of the pool.
+///
+/// Has no effect once the pool has been started by the first proving task.
+pub fn set_threads(threads: usize) {
+    THREADS.store(threads, Ordering::SeqCst);
+}
+
+/// Run `task` on the prover pool with a low priority and wait for its
+/// result.
+pub async fn spawn<F, T>(task: F) -> Result<T, Status>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    spawn_with_priority(Priority::Low, task).await
+}
+
+/// Run `task` on the prover pool once no task of a higher priority is
+/// waiting, and wait for its result.
+pub async fn spawn_with_priority<F, T>(
+    priority: Priority,
+    task: F,
+) -> Result<T, Status>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+
+    let job: Job = Box::new(move || {
+        // The receiver is only gone if the request was dropped, in which
+        // case nobody is waiting for the result.
+        let _ = sender.send(task());
+    });
+    QUEUES
+        .lock()
+        .expect("Prover queues poisoned")
+        .push(priority, job);
+
+    // Every queued job spawns one pool task, which runs whichever job is
+    // the most urgent once a thread picks it up.
+    POOL.spawn(|| {
+        let job = QUEUES.lock().expect("Prover queues poisoned").pop();
+        if let Some(job) = job {
+            job();
+        }
+    });
+
+    receiver
+        .await
+        .map_err(|_| Status::internal("The proving task did not complete"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn pop_runs_high_priority_jobs_first() {
+        let order = Arc::new(Mutex::new(vec![]));
+        let job = |n: usize| -> Job {
+            let order = order.clone();
+            Box::new(move || order.lock().unwrap().push(n))
+        };
+
+        let mut queues = Queues::default();
+        queues.push(Priority::Low, job(0));
+        queues.push(Priority::Low, job(1));
+        queues.push(Priority::High, job(2));
+        queues.push(Priority::High, job(3));
+
+        while let Some(job) = queues.pop() {
+            job();
+        }
+
+        // High priority first, each tier in the order it was queued.
+        assert_eq!(*order.lock().unwrap(), vec![2, 3, 0, 1]);
+    }
+}
@@ -0,0 +1,166 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Per-peer rate limiting for the Rusk services.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Instant;
+use tonic::{Request, Status};
+
+/// Peers tracked at most. The least recently seen one is forgotten to make
+/// room for a new peer.
+const MAX_TRACKED_PEERS: usize = 10_000;
+
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+}
+
+/// Token bucket limiter allowing every peer `rate` requests per second,
+/// with bursts of up to `rate` requests.
+///
+/// Peers are told apart by their IP address. The ones connected through a
+/// Unix socket have none, and share a single bucket.
+pub struct RateLimiter {
+    rate: f64,
+    max_peers: usize,
+    buckets: Mutex<HashMap<Option<IpAddr>, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(rate: u32) -> Self {
+        Self::with_max_peers(rate, MAX_TRACKED_PEERS)
+    }
+
+    fn with_max_peers(rate: u32, max_peers: usize) -> Self {
+        Self {
+            rate: rate as f64,
+            max_peers,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take a token from the bucket of `peer`, returning whether there was
+    /// one left.
+    pub fn acquire(&self, peer: Option<IpAddr>) -> bool {
+        self.acquire_at(peer, Instant::now())
+    }
+
+    fn acquire_at(&self, peer: Option<IpAddr>, now: Instant) -> bool {
+        let mut buckets = self.buckets.lock().expect("Poisoned rate limiter");
+
+        // Only a new peer can grow the map past the cap, so the scan for the
+        // least recently seen bucket is limited to its first request.
+        if buckets.len() >= self.max_peers && !buckets.contains_key(&peer) {
+            let oldest = buckets
+                .iter()
+                .min_by_key(|(_, bucket)| bucket.last)
+                .map(|(peer, _)| *peer);
+            if let Some(oldest) = oldest {
+                buckets.remove(&oldest);
+            }
+        }
+
+        let rate = self.rate;
+        let bucket = buckets.entry(peer).or_insert(Bucket {
+            tokens: rate,
+            last: now,
+        });
+
+        let elapsed = now.saturating_duration_since(bucket.last).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(rate);
+        bucket.last = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Build an interceptor rejecting the requests of the peers exceeding the
+/// `rate` requests per second.
+pub fn rate_limit_interceptor(
+    rate: u32,
+) -> impl Fn(Request<()>) -> Result<Request<()>, Status> + Send + Sync + 'static
+{
+    let limiter = RateLimiter::new(rate);
+
+    move |request: Request<()>| {
+        let peer = request.remote_addr().map(|addr| addr.ip());
+
+        if limiter.acquire(peer) {
+            Ok(request)
+        } else {
+            Err(Status::resource_exhausted("Rate limit exceeded"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+    use std::time::Duration;
+
+    fn peer(n: u8) -> Option<IpAddr> {
+        Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, n)))
+    }
+
+    #[test]
+    fn acquire_refills_over_time() {
+        let limiter = RateLimiter::new(2);
+        let now = Instant::now();
+
+        assert!(limiter.acquire_at(peer(1), now));
+        assert!(limiter.acquire_at(peer(1), now));
+        assert!(!limiter.acquire_at(peer(1), now));
+
+        // Half a second refills one token at two per second.
+        let later = now + Duration::from_millis(500);
+        assert!(limiter.acquire_at(peer(1), later));
+        assert!(!limiter.acquire_at(peer(1), later));
+
+        // The bucket never holds more than `rate` tokens.
+        let much_later = later + Duration::from_secs(60);
+        assert!(limiter.acquire_at(peer(1), much_later));
+        assert!(limiter.acquire_at(peer(1), much_later));
+        assert!(!limiter.acquire_at(peer(1), much_later));
+    }
+
+    #[test]
+    fn acquire_evicts_the_least_recently_seen_peer() {
+        let limiter = RateLimiter::with_max_peers(1, 2);
+        let now = Instant::now();
+
+        assert!(limiter.acquire_at(peer(1), now));
+        assert!(limiter.acquire_at(peer(2), now + Duration::from_millis(1)));
+        assert!(limiter.acquire_at(peer(3), now + Duration::from_millis(2)));
+
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets.len(), 2);
+        assert!(!buckets.contains_key(&peer(1)));
+        assert!(buckets.contains_key(&peer(2)));
+        assert!(buckets.contains_key(&peer(3)));
+    }
+
+    #[test]
+    fn known_peers_are_not_evicted() {
+        let limiter = RateLimiter::with_max_peers(1, 2);
+        let now = Instant::now();
+
+        assert!(limiter.acquire_at(peer(1), now));
+        assert!(limiter.acquire_at(peer(2), now));
+        // A tracked peer keeps its empty bucket, even with the map full.
+        assert!(!limiter.acquire_at(peer(1), now));
+
+        assert_eq!(limiter.buckets.lock().unwrap().len(), 2);
+    }
+}
@@ -4,9 +4,13 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+pub mod admin;
 pub mod blindbid;
 pub mod echoer;
 pub mod pki;
+pub mod prover;
+pub mod proving_key;
+pub mod verifier;
 use tonic::{Request, Response, Status};
 
 pub mod rusk_proto {
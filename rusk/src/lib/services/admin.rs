@@ -0,0 +1,38 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Admin service implementation for the Rusk server.
+
+use super::rusk_proto;
+use crate::encoding;
+use crate::Rusk;
+use tonic::{Request, Response, Status};
+use tracing::{info, warn};
+
+// Re-export the main types for Admin Service.
+pub use rusk_proto::admin_client::AdminClient;
+pub use rusk_proto::admin_server::{Admin, AdminServer};
+pub use rusk_proto::{VerifyCrsRequest, VerifyCrsResponse};
+
+#[tonic::async_trait]
+impl Admin for Rusk {
+    async fn verify_crs(
+        &self,
+        _request: Request<VerifyCrsRequest>,
+    ) -> Result<Response<VerifyCrsResponse>, Status> {
+        info!("Recieved VerifyCrs request");
+        // Hashing the whole CRS would hold the runtime for seconds.
+        let valid = tokio::task::spawn_blocking(crate::verify_crs)
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+        let valid = encoding::as_status_err(valid)?;
+        if !valid {
+            warn!("The CRS in the profile failed the integrity check");
+        }
+
+        Ok(Response::new(VerifyCrsResponse { valid }))
+    }
+}
@@ -11,6 +11,10 @@ mod verify_score_handler;
 use super::rusk_proto;
 use crate::services::ServiceRequestHandler;
 use crate::Rusk;
+use dusk_blindbid::{Bid, BlindBidError, Score};
+use dusk_bls12_381::BlsScalar;
+use dusk_plonk::jubjub::JubJubAffine;
+use dusk_poseidon::tree::PoseidonBranch;
 use score_gen_handler::ScoreGenHandler;
 use tonic::{Request, Response, Status};
 use tracing::{info, warn};
@@ -27,7 +31,30 @@ pub use rusk_proto::blind_bid_service_server::{
     BlindBidService, BlindBidServiceServer,
 };
 
-pub(crate) const BLINDBID_TRANSCRIPT_INIT: &'static [u8] = b"dusk-network";
+/// Compute the [`Score`] of a [`Bid`] for the given consensus parameters.
+///
+/// The arguments are forwarded to [`Score::compute`] in the exact order the
+/// `BlindBidCircuit` expects them, using the root of the provided branch as
+/// the Bid tree root.
+pub fn compute_bid_score(
+    bid: &Bid,
+    secret: &JubJubAffine,
+    secret_k: BlsScalar,
+    branch: &PoseidonBranch<17>,
+    seed: BlsScalar,
+    latest_consensus_round: u64,
+    latest_consensus_step: u64,
+) -> Result<Score, BlindBidError> {
+    Score::compute(
+        bid,
+        secret,
+        secret_k,
+        *branch.root(),
+        seed,
+        latest_consensus_round,
+        latest_consensus_step,
+    )
+}
 
 #[tonic::async_trait]
 impl BlindBidService for Rusk {
@@ -35,9 +62,15 @@ impl BlindBidService for Rusk {
         &self,
         request: Request<GenerateScoreRequest>,
     ) -> Result<Response<GenerateScoreResponse>, Status> {
-        let handler = ScoreGenHandler::load_request(&request);
         info!("Recieved Score generation request");
-        match handler.handle_request() {
+        // Generating the score includes the BlindBid proof, so the handler
+        // runs on the prover pool.
+        let res = crate::prover_pool::spawn(move || {
+            ScoreGenHandler::load_request(&request).handle_request()
+        })
+        .await
+        .and_then(|res| res);
+        match res {
             Ok(response) => {
                 info!("Score generation request was successfully processed. Sending response..");
                 Ok(response)
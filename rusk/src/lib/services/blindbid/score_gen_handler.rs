@@ -9,7 +9,7 @@ use super::{GenerateScoreRequest, GenerateScoreResponse};
 use crate::encoding;
 use anyhow::Result;
 use blindbid_circuits::BlindBidCircuit;
-use dusk_blindbid::{Bid, Score};
+use dusk_blindbid::Bid;
 use dusk_bls12_381::BlsScalar;
 use dusk_bytes::DeserializableSlice;
 use dusk_bytes::Serializable;
@@ -50,11 +50,11 @@ where
         // Generate Score for the Bid
         let latest_consensus_round = self.request.get_ref().round as u64;
         let latest_consensus_step = self.request.get_ref().step as u64;
-        let score = Score::compute(
+        let score = super::compute_bid_score(
             &bid,
             &secret,
             k,
-            *branch.root(),
+            &branch,
             seed,
             latest_consensus_round,
             latest_consensus_step,
@@ -119,7 +119,7 @@ fn gen_blindbid_proof(circuit: &mut BlindBidCircuit) -> Result<Proof> {
         .gen_proof(
             &crate::PUB_PARAMS,
             &prover_key,
-            super::BLINDBID_TRANSCRIPT_INIT,
+            crate::circuits::transcript_label(&BlindBidCircuit::CIRCUIT_ID),
         )
         .map_err(|e| anyhow::anyhow!("{:?}", e))
 }
@@ -121,7 +121,7 @@ fn verify_blindbid_proof(
         proof,
         &pi,
         &vd.pi_pos(),
-        super::BLINDBID_TRANSCRIPT_INIT,
+        crate::circuits::transcript_label(&BlindBidCircuit::CIRCUIT_ID),
     )
     .map_err(|e| anyhow::anyhow!("{:?}", e))
 }
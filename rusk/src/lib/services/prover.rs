@@ -0,0 +1,426 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Prover service implementation for the Rusk server.
+mod prove_bid_handler;
+mod prove_stco_handler;
+mod prove_stct_handler;
+mod prove_wfo_handler;
+mod stake_proof_inputs_handler;
+
+use super::rusk_proto;
+use crate::services::ServiceRequestHandler;
+use crate::Rusk;
+use anyhow::Result;
+use bid_circuits::BidCorrectnessCircuit;
+use blindbid_circuits::BlindBidCircuit;
+use dusk_blindbid::Bid;
+use dusk_bytes::Serializable;
+use dusk_pki::{PublicSpendKey, ViewKey};
+use dusk_plonk::jubjub::{GENERATOR_EXTENDED, GENERATOR_NUMS_EXTENDED};
+use dusk_plonk::prelude::*;
+use dusk_poseidon::tree::PoseidonBranch;
+use dusk_schnorr::Signature;
+use phoenix_core::{Crossover, Fee, Message, Note};
+use prove_bid_handler::ProveBidHandler;
+use prove_stco_handler::ProveStcoHandler;
+use prove_stct_handler::ProveStctHandler;
+use prove_wfo_handler::ProveWfoHandler;
+use stake_proof_inputs_handler::StakeProofInputsHandler;
+use tonic::{Request, Response, Status};
+use tracing::{info, warn};
+
+pub use super::rusk_proto::{
+    ProveBidRequest, ProveResponse, ProveStcoRequest, ProveStctRequest,
+    ProveWfoRequest, StakeProofInputsRequest, StakeProofInputsResponse,
+};
+
+// Re-export the circuits whose signatures the clients have to produce.
+pub use transfer_circuits::{
+    SendToContractObfuscatedCircuit, SendToContractTransparentCircuit,
+    WithdrawFromObfuscatedCircuit,
+};
+
+// Re-export the main types for Prover Service.
+pub use rusk_proto::prover_client::ProverClient;
+pub use rusk_proto::prover_server::{Prover, ProverServer};
+
+/// Prove the correctness of the Bid committing to `value` with `blinder`.
+///
+/// The `BidCorrectnessCircuit` is filled the same way it is when its keys are
+/// compiled, so the proof verifies against the cached verifier data.
+pub fn prove_bid(
+    commitment: JubJubAffine,
+    value: JubJubScalar,
+    blinder: JubJubScalar,
+) -> Result<(Proof, Vec<BlsScalar>)> {
+    let opening = JubJubAffine::from(
+        (GENERATOR_EXTENDED * value) + (GENERATOR_NUMS_EXTENDED * blinder),
+    );
+    if opening != commitment {
+        anyhow::bail!("the commitment doesn't open to the value and blinder");
+    }
+
+    prove(&mut BidCorrectnessCircuit {
+        commitment,
+        value,
+        blinder,
+    })
+}
+
+/// Prove the `Score` of a Bid for the given consensus parameters.
+///
+/// The `Score` is computed as the `BlindBidCircuit` keys compilation does,
+/// and a descriptive error is returned when the parameters don't allow it,
+/// e.g. when the Bid is expired or not yet eligible.
+pub fn prove_blindbid(
+    bid: Bid,
+    secret: JubJubAffine,
+    secret_k: BlsScalar,
+    branch: &PoseidonBranch<17>,
+    seed: BlsScalar,
+    latest_consensus_round: u64,
+    latest_consensus_step: u64,
+) -> Result<(Proof, Vec<BlsScalar>)> {
+    let score = super::blindbid::compute_bid_score(
+        &bid,
+        &secret,
+        secret_k,
+        branch,
+        seed,
+        latest_consensus_round,
+        latest_consensus_step,
+    )
+    .map_err(|e| {
+        anyhow::anyhow!(
+            "score computation failed at round {} step {}: {:?}",
+            latest_consensus_round,
+            latest_consensus_step,
+            e
+        )
+    })?;
+
+    prove(&mut BlindBidCircuit {
+        bid,
+        score,
+        secret_k,
+        secret,
+        seed,
+        latest_consensus_round: BlsScalar::from(latest_consensus_round),
+        latest_consensus_step: BlsScalar::from(latest_consensus_step),
+        branch,
+    })
+}
+
+/// Prove a Send-To-Contract-Transparent transaction of the `crossover`
+/// value to the contract at `address`.
+///
+/// The circuit is built as when its keys are compiled. An error describes
+/// the inputs it can't be built from, e.g. a crossover `vk` can't decrypt.
+pub fn prove_stct(
+    fee: Fee,
+    crossover: Crossover,
+    vk: &ViewKey,
+    address: BlsScalar,
+    signature: Signature,
+) -> Result<(Proof, Vec<BlsScalar>)> {
+    let mut circuit = SendToContractTransparentCircuit::new(
+        fee, crossover, vk, address, signature,
+    )
+    .map_err(|e| anyhow::anyhow!("invalid STCT inputs: {:?}", e))?;
+
+    prove(&mut circuit)
+}
+
+/// Public inputs the STCT proof of staking `value` of the `crossover` with
+/// the contract at `caller` must satisfy.
+///
+/// They are the ones [`prove_stct`] returns for the same transaction: the
+/// value commitment and the `fee` stealth key coordinates, the signed message
+/// and the value, so a wallet can check its proof before sending it.
+pub fn stake_proof_inputs(
+    fee: &Fee,
+    crossover: &Crossover,
+    value: u64,
+    caller: &BlsScalar,
+) -> Vec<BlsScalar> {
+    let value_commitment = JubJubAffine::from(crossover.value_commitment());
+    let pk = JubJubAffine::from(fee.stealth_address().pk_r().as_ref());
+    let message = SendToContractTransparentCircuit::sign_message(
+        crossover, value, caller,
+    );
+
+    vec![
+        value_commitment.get_x(),
+        value_commitment.get_y(),
+        pk.get_x(),
+        pk.get_y(),
+        message,
+        BlsScalar::from(value),
+    ]
+}
+
+/// Whether the stake withdrawal into `note` pays `expected_value` to the
+/// owner of `vk`.
+///
+/// This is the value check the withdrawal is accepted on, so a wallet can
+/// make it before sending the withdrawal. A note `vk` can't decrypt pays
+/// nothing to its owner.
+pub fn can_withdraw_to(note: &Note, vk: &ViewKey, expected_value: u64) -> bool {
+    note.value(Some(vk)) == Ok(expected_value)
+}
+
+/// Prove a Send-To-Contract-Obfuscated transaction of the `crossover` value
+/// to the contract at `address`, as the `message` sent to `message_psk`.
+///
+/// The `signature` is the one [`SendToContractObfuscatedCircuit::sign`]
+/// produces. An error describes the inputs the circuit can't be built from.
+#[allow(clippy::too_many_arguments)]
+pub fn prove_stco(
+    fee: Fee,
+    crossover: Crossover,
+    vk: &ViewKey,
+    signature: Signature,
+    public_message_pk: bool,
+    message: Message,
+    message_psk: &PublicSpendKey,
+    message_r: JubJubScalar,
+    address: BlsScalar,
+) -> Result<(Proof, Vec<BlsScalar>)> {
+    let mut circuit = SendToContractObfuscatedCircuit::new(
+        fee,
+        crossover,
+        vk,
+        signature,
+        public_message_pk,
+        message,
+        message_psk,
+        message_r,
+        address,
+    )
+    .map_err(|e| anyhow::anyhow!("invalid STCO inputs: {:?}", e))?;
+
+    prove(&mut circuit)
+}
+
+/// Prove a Withdraw-From-Obfuscated transaction of the `input` note into
+/// the `change` message and the `output` note.
+///
+/// The view keys are only needed for obfuscated notes. The proof isn't
+/// attempted unless the input value is the sum of the change and output.
+pub fn prove_wfo(
+    input: &Note,
+    input_vk: Option<&ViewKey>,
+    change: &Message,
+    change_r: JubJubScalar,
+    change_psk: &PublicSpendKey,
+    output: &Note,
+    output_vk: Option<&ViewKey>,
+) -> Result<(Proof, Vec<BlsScalar>)> {
+    let invalid = |e| anyhow::anyhow!("invalid WFO inputs: {:?}", e);
+
+    let input_value = input.value(input_vk).map_err(invalid)?;
+    let (change_value, _) =
+        change.decrypt(&change_r, change_psk).map_err(invalid)?;
+    let output_value = output.value(output_vk).map_err(invalid)?;
+
+    if Some(input_value) != change_value.checked_add(output_value) {
+        anyhow::bail!(
+            "the input value {} isn't the change {} plus the output {}",
+            input_value,
+            change_value,
+            output_value
+        );
+    }
+
+    let mut circuit = WithdrawFromObfuscatedCircuit::new(
+        input, input_vk, change, change_r, change_psk, output, output_vk,
+    )
+    .map_err(invalid)?;
+
+    prove(&mut circuit)
+}
+
+// Generate a proof for a circuit instance loaded with the desired inputs,
+// using its cached keys.
+//
+// The proof comes along with the public inputs it was generated for, in the
+// flat order the Verifier service expects them.
+fn prove<C: Circuit>(circuit: &mut C) -> Result<(Proof, Vec<BlsScalar>)> {
+    let keys = rusk_profile::keys_for(&C::CIRCUIT_ID)?;
+
+    let prover_key = ProverKey::from_slice(&keys.get_prover()?)?;
+    let vd = VerifierData::from_slice(&keys.get_verifier()?)?;
+    let proof = circuit
+        .gen_proof(
+            &crate::PUB_PARAMS,
+            &prover_key,
+            crate::circuits::transcript_label(&C::CIRCUIT_ID),
+        )
+        .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+
+    // The composer holds the negated public inputs, at the positions the
+    // verifier data lists them.
+    let mut composer = StandardComposer::new();
+    circuit
+        .gadget(&mut composer)
+        .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+    let dense_pi = composer.construct_dense_pi_vec();
+    let pi = vd.pi_pos().iter().map(|&pos| -dense_pi[pos]).collect();
+
+    Ok((proof, pi))
+}
+
+// Answer with `Unimplemented` the requests for a circuit whose keys the node
+// lacks, before decoding them.
+fn require_keys<C: Circuit>() -> Result<(), Status> {
+    crate::circuits::cached_keys(&C::CIRCUIT_ID).map(|_| ())
+}
+
+// Build the response carrying a proof along with its public inputs, so they
+// can't be told apart on their way to a verifier.
+fn prove_response((proof, pi): (Proof, Vec<BlsScalar>)) -> ProveResponse {
+    ProveResponse {
+        proof: proof.to_bytes().to_vec(),
+        public_inputs: pi.iter().map(|pi| pi.to_bytes().to_vec()).collect(),
+    }
+}
+
+#[tonic::async_trait]
+impl Prover for Rusk {
+    async fn prove_bid(
+        &self,
+        request: Request<ProveBidRequest>,
+    ) -> Result<Response<ProveResponse>, Status> {
+        info!("Recieved ProveBid request");
+        let res = crate::prover_pool::spawn(move || {
+            ProveBidHandler::load_request(&request).handle_request()
+        })
+        .await
+        .and_then(|res| res);
+        match res {
+            Ok(response) => {
+                info!("ProveBid request was successfully processed. Sending response..");
+                Ok(response)
+            }
+            Err(e) => {
+                warn!("An error ocurred during the ProveBid request processing: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+
+    async fn prove_stco(
+        &self,
+        request: Request<ProveStcoRequest>,
+    ) -> Result<Response<ProveResponse>, Status> {
+        info!("Recieved ProveStco request");
+        let res = crate::prover_pool::spawn(move || {
+            ProveStcoHandler::load_request(&request).handle_request()
+        })
+        .await
+        .and_then(|res| res);
+        match res {
+            Ok(response) => {
+                info!("ProveStco request was successfully processed. Sending response..");
+                Ok(response)
+            }
+            Err(e) => {
+                warn!("An error ocurred during the ProveStco request processing: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+
+    async fn prove_wfo(
+        &self,
+        request: Request<ProveWfoRequest>,
+    ) -> Result<Response<ProveResponse>, Status> {
+        info!("Recieved ProveWfo request");
+        let res = crate::prover_pool::spawn(move || {
+            ProveWfoHandler::load_request(&request).handle_request()
+        })
+        .await
+        .and_then(|res| res);
+        match res {
+            Ok(response) => {
+                info!("ProveWfo request was successfully processed. Sending response..");
+                Ok(response)
+            }
+            Err(e) => {
+                warn!("An error ocurred during the ProveWfo request processing: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+
+    async fn prove_stct(
+        &self,
+        request: Request<ProveStctRequest>,
+    ) -> Result<Response<ProveResponse>, Status> {
+        info!("Recieved ProveStct request");
+        let res = crate::prover_pool::spawn(move || {
+            ProveStctHandler::load_request(&request).handle_request()
+        })
+        .await
+        .and_then(|res| res);
+        match res {
+            Ok(response) => {
+                info!("ProveStct request was successfully processed. Sending response..");
+                Ok(response)
+            }
+            Err(e) => {
+                warn!("An error ocurred during the ProveStct request processing: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+
+    async fn stake_proof_inputs(
+        &self,
+        request: Request<StakeProofInputsRequest>,
+    ) -> Result<Response<StakeProofInputsResponse>, Status> {
+        info!("Recieved StakeProofInputs request");
+        match StakeProofInputsHandler::load_request(&request).handle_request() {
+            Ok(response) => {
+                info!("StakeProofInputs request was successfully processed. Sending response..");
+                Ok(response)
+            }
+            Err(e) => {
+                warn!("An error ocurred during the StakeProofInputs request processing: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dusk_pki::SecretSpendKey;
+
+    #[test]
+    fn can_withdraw_to_the_note_owner_only() {
+        let rng = &mut rand::thread_rng();
+
+        let ssk = SecretSpendKey::random(rng);
+        let vk = ssk.view_key();
+        let psk = ssk.public_spend_key();
+
+        let obfuscated =
+            Note::obfuscated(rng, &psk, 100, JubJubScalar::random(rng));
+        let transparent = Note::transparent(rng, &psk, 100);
+
+        for note in [&obfuscated, &transparent].iter() {
+            assert!(can_withdraw_to(note, &vk, 100));
+            assert!(!can_withdraw_to(note, &vk, 99));
+        }
+
+        // Someone else can't decrypt the obfuscated note.
+        let other = SecretSpendKey::random(rng).view_key();
+        assert!(!can_withdraw_to(&obfuscated, &other, 100));
+    }
+}
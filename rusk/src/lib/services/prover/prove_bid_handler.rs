@@ -0,0 +1,48 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::super::ServiceRequestHandler;
+use super::{ProveBidRequest, ProveResponse};
+use crate::encoding;
+use bid_circuits::BidCorrectnessCircuit;
+use dusk_bytes::DeserializableSlice;
+use dusk_plonk::prelude::*;
+use tonic::{Code, Request, Response, Status};
+
+/// Implementation of the ProveBid Handler.
+pub struct ProveBidHandler<'a> {
+    request: &'a Request<ProveBidRequest>,
+}
+
+impl<'a, 'b> ServiceRequestHandler<'a, 'b, ProveBidRequest, ProveResponse>
+    for ProveBidHandler<'a>
+where
+    'b: 'a,
+{
+    fn load_request(request: &'b Request<ProveBidRequest>) -> Self {
+        Self { request }
+    }
+
+    fn handle_request(&self) -> Result<Response<ProveResponse>, Status> {
+        super::require_keys::<BidCorrectnessCircuit>()?;
+        let request = self.request.get_ref();
+
+        let commitment = encoding::as_status_err(JubJubAffine::from_slice(
+            &request.commitment[..],
+        ))?;
+        let value = encoding::as_status_err(JubJubScalar::from_slice(
+            &request.value[..],
+        ))?;
+        let blinder = encoding::as_status_err(JubJubScalar::from_slice(
+            &request.blinder[..],
+        ))?;
+
+        let proved = super::prove_bid(commitment, value, blinder)
+            .map_err(|e| Status::new(Code::Unknown, format!("{}", e)))?;
+
+        Ok(Response::new(super::prove_response(proved)))
+    }
+}
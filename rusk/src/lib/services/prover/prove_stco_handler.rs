@@ -0,0 +1,69 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::super::ServiceRequestHandler;
+use super::{ProveResponse, ProveStcoRequest, SendToContractObfuscatedCircuit};
+use crate::encoding;
+use dusk_bls12_381::BlsScalar;
+use dusk_bytes::DeserializableSlice;
+use dusk_jubjub::JubJubScalar;
+use dusk_pki::{PublicSpendKey, ViewKey};
+use dusk_schnorr::Signature;
+use phoenix_core::{Crossover, Fee, Message};
+use tonic::{Code, Request, Response, Status};
+
+/// Implementation of the ProveStco Handler.
+pub struct ProveStcoHandler<'a> {
+    request: &'a Request<ProveStcoRequest>,
+}
+
+impl<'a, 'b> ServiceRequestHandler<'a, 'b, ProveStcoRequest, ProveResponse>
+    for ProveStcoHandler<'a>
+where
+    'b: 'a,
+{
+    fn load_request(request: &'b Request<ProveStcoRequest>) -> Self {
+        Self { request }
+    }
+
+    fn handle_request(&self) -> Result<Response<ProveResponse>, Status> {
+        super::require_keys::<SendToContractObfuscatedCircuit>()?;
+        let request = self.request.get_ref();
+
+        let fee = encoding::as_status_err(Fee::from_slice(&request.fee))?;
+        let crossover =
+            encoding::as_status_err(Crossover::from_slice(&request.crossover))?;
+        let vk =
+            encoding::as_status_err(ViewKey::from_slice(&request.view_key))?;
+        let signature =
+            encoding::as_status_err(Signature::from_slice(&request.signature))?;
+        let message =
+            encoding::as_status_err(Message::from_slice(&request.message))?;
+        let message_psk = encoding::as_status_err(PublicSpendKey::from_slice(
+            &request.message_psk,
+        ))?;
+        let message_r = encoding::as_status_err(JubJubScalar::from_slice(
+            &request.message_r,
+        ))?;
+        let address =
+            encoding::as_status_err(BlsScalar::from_slice(&request.address))?;
+
+        let proved = super::prove_stco(
+            fee,
+            crossover,
+            &vk,
+            signature,
+            request.public_message_pk,
+            message,
+            &message_psk,
+            message_r,
+            address,
+        )
+        .map_err(|e| Status::new(Code::Unknown, format!("{}", e)))?;
+
+        Ok(Response::new(super::prove_response(proved)))
+    }
+}
@@ -0,0 +1,52 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::super::ServiceRequestHandler;
+use super::{
+    ProveResponse, ProveStctRequest, SendToContractTransparentCircuit,
+};
+use crate::encoding;
+use dusk_bls12_381::BlsScalar;
+use dusk_bytes::DeserializableSlice;
+use dusk_pki::ViewKey;
+use dusk_schnorr::Signature;
+use phoenix_core::{Crossover, Fee};
+use tonic::{Code, Request, Response, Status};
+
+/// Implementation of the ProveStct Handler.
+pub struct ProveStctHandler<'a> {
+    request: &'a Request<ProveStctRequest>,
+}
+
+impl<'a, 'b> ServiceRequestHandler<'a, 'b, ProveStctRequest, ProveResponse>
+    for ProveStctHandler<'a>
+where
+    'b: 'a,
+{
+    fn load_request(request: &'b Request<ProveStctRequest>) -> Self {
+        Self { request }
+    }
+
+    fn handle_request(&self) -> Result<Response<ProveResponse>, Status> {
+        super::require_keys::<SendToContractTransparentCircuit>()?;
+        let request = self.request.get_ref();
+
+        let fee = encoding::as_status_err(Fee::from_slice(&request.fee))?;
+        let crossover =
+            encoding::as_status_err(Crossover::from_slice(&request.crossover))?;
+        let vk =
+            encoding::as_status_err(ViewKey::from_slice(&request.view_key))?;
+        let address =
+            encoding::as_status_err(BlsScalar::from_slice(&request.address))?;
+        let signature =
+            encoding::as_status_err(Signature::from_slice(&request.signature))?;
+
+        let proved = super::prove_stct(fee, crossover, &vk, address, signature)
+            .map_err(|e| Status::new(Code::Unknown, format!("{}", e)))?;
+
+        Ok(Response::new(super::prove_response(proved)))
+    }
+}
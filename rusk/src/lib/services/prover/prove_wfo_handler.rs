@@ -0,0 +1,69 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::super::ServiceRequestHandler;
+use super::{ProveResponse, ProveWfoRequest, WithdrawFromObfuscatedCircuit};
+use crate::encoding;
+use dusk_bytes::DeserializableSlice;
+use dusk_jubjub::JubJubScalar;
+use dusk_pki::{PublicSpendKey, ViewKey};
+use phoenix_core::{Message, Note};
+use tonic::{Code, Request, Response, Status};
+
+/// Implementation of the ProveWfo Handler.
+pub struct ProveWfoHandler<'a> {
+    request: &'a Request<ProveWfoRequest>,
+}
+
+impl<'a, 'b> ServiceRequestHandler<'a, 'b, ProveWfoRequest, ProveResponse>
+    for ProveWfoHandler<'a>
+where
+    'b: 'a,
+{
+    fn load_request(request: &'b Request<ProveWfoRequest>) -> Self {
+        Self { request }
+    }
+
+    fn handle_request(&self) -> Result<Response<ProveResponse>, Status> {
+        super::require_keys::<WithdrawFromObfuscatedCircuit>()?;
+        let request = self.request.get_ref();
+
+        let input = encoding::as_status_err(Note::from_slice(&request.input))?;
+        let input_vk = parse_view_key(&request.input_view_key)?;
+        let change =
+            encoding::as_status_err(Message::from_slice(&request.change))?;
+        let change_r = encoding::as_status_err(JubJubScalar::from_slice(
+            &request.change_r,
+        ))?;
+        let change_psk = encoding::as_status_err(PublicSpendKey::from_slice(
+            &request.change_psk,
+        ))?;
+        let output =
+            encoding::as_status_err(Note::from_slice(&request.output))?;
+        let output_vk = parse_view_key(&request.output_view_key)?;
+
+        let proved = super::prove_wfo(
+            &input,
+            input_vk.as_ref(),
+            &change,
+            change_r,
+            &change_psk,
+            &output,
+            output_vk.as_ref(),
+        )
+        .map_err(|e| Status::new(Code::Unknown, format!("{}", e)))?;
+
+        Ok(Response::new(super::prove_response(proved)))
+    }
+}
+
+// The view key of a transparent note is sent empty.
+fn parse_view_key(bytes: &[u8]) -> Result<Option<ViewKey>, Status> {
+    if bytes.is_empty() {
+        return Ok(None);
+    }
+    encoding::as_status_err(ViewKey::from_slice(bytes)).map(Some)
+}
@@ -0,0 +1,52 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::super::ServiceRequestHandler;
+use super::{StakeProofInputsRequest, StakeProofInputsResponse};
+use crate::encoding;
+use dusk_bls12_381::BlsScalar;
+use dusk_bytes::{DeserializableSlice, Serializable};
+use phoenix_core::{Crossover, Fee};
+use tonic::{Request, Response, Status};
+
+/// Implementation of the StakeProofInputs Handler.
+pub struct StakeProofInputsHandler<'a> {
+    request: &'a Request<StakeProofInputsRequest>,
+}
+
+impl<'a, 'b>
+    ServiceRequestHandler<
+        'a,
+        'b,
+        StakeProofInputsRequest,
+        StakeProofInputsResponse,
+    > for StakeProofInputsHandler<'a>
+where
+    'b: 'a,
+{
+    fn load_request(request: &'b Request<StakeProofInputsRequest>) -> Self {
+        Self { request }
+    }
+
+    fn handle_request(
+        &self,
+    ) -> Result<Response<StakeProofInputsResponse>, Status> {
+        let request = self.request.get_ref();
+
+        let fee = encoding::as_status_err(Fee::from_slice(&request.fee))?;
+        let crossover =
+            encoding::as_status_err(Crossover::from_slice(&request.crossover))?;
+        let caller =
+            encoding::as_status_err(BlsScalar::from_slice(&request.caller))?;
+
+        let pi =
+            super::stake_proof_inputs(&fee, &crossover, request.value, &caller);
+
+        Ok(Response::new(StakeProofInputsResponse {
+            public_inputs: pi.iter().map(|pi| pi.to_bytes().to_vec()).collect(),
+        }))
+    }
+}
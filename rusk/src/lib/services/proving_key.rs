@@ -0,0 +1,51 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Proving key service implementation for the Rusk server.
+//!
+//! Prover keys are large and let anyone prove on behalf of the node, so the
+//! service is meant to be served behind [`crate::auth::token_interceptor`].
+
+use super::rusk_proto;
+use crate::Rusk;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+// Re-export the main types for ProvingKey Service.
+pub use rusk_proto::proving_key_service_client::ProvingKeyServiceClient;
+pub use rusk_proto::proving_key_service_server::{
+    ProvingKeyService, ProvingKeyServiceServer,
+};
+pub use rusk_proto::{ProvingKeyRequest, ProvingKeyResponse};
+
+#[tonic::async_trait]
+impl ProvingKeyService for Rusk {
+    async fn get_proving_key(
+        &self,
+        request: Request<ProvingKeyRequest>,
+    ) -> Result<Response<ProvingKeyResponse>, Status> {
+        info!("Recieved GetProvingKey request");
+        let circuit_id = &request.get_ref().circuit_id;
+        if circuit_id.len() != 32 {
+            return Err(Status::invalid_argument(
+                "The circuit id must be 32 bytes long",
+            ));
+        }
+
+        let mut id = [0u8; 32];
+        id.copy_from_slice(circuit_id);
+
+        let proving_key =
+            rusk_profile::proving_key_for(&id).ok_or_else(|| {
+                Status::not_found(format!(
+                    "No keys cached for circuit {}",
+                    hex::encode(id)
+                ))
+            })?;
+
+        Ok(Response::new(ProvingKeyResponse { proving_key }))
+    }
+}
@@ -0,0 +1,457 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Verifier service implementation for the Rusk server.
+
+mod fingerprint_handler;
+mod supported_circuits_handler;
+mod verify_any_handler;
+mod verify_dry_handler;
+mod verify_handler;
+mod verify_with_vd_handler;
+
+use super::rusk_proto;
+use crate::encoding;
+use crate::services::ServiceRequestHandler;
+use crate::Rusk;
+use dusk_bls12_381::BlsScalar;
+use dusk_bytes::{DeserializableSlice, Serializable};
+use dusk_plonk::prelude::*;
+use dusk_poseidon::sponge;
+use fingerprint_handler::KeysFingerprintHandler;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use supported_circuits_handler::SupportedCircuitsHandler;
+use tonic::{Request, Response, Status, Streaming};
+use tracing::{error, info, warn};
+use verify_any_handler::VerifyAnyHandler;
+use verify_dry_handler::VerifyDryHandler;
+use verify_handler::VerifyHandler;
+use verify_with_vd_handler::VerifyWithVdHandler;
+
+pub use super::rusk_proto::{
+    KeysFingerprintRequest, KeysFingerprintResponse, SupportedCircuitsRequest,
+    SupportedCircuitsResponse, VerifyAnyRequest, VerifyAnyResponse,
+    VerifyBatchResponse, VerifyDryResponse, VerifyRequest, VerifyResponse,
+    VerifyWithVdRequest,
+};
+
+// Re-export the main types for Verifier Service.
+pub use rusk_proto::verifier_client::VerifierClient;
+pub use rusk_proto::verifier_server::{Verifier, VerifierServer};
+
+/// Proofs a single VerifyBatch stream can carry.
+const MAX_BATCH_SIZE: usize = 256;
+
+lazy_static! {
+    static ref MAX_PROOF_AGES: RwLock<HashMap<[u8; 32], (usize, Duration)>> =
+        RwLock::new(HashMap::new());
+}
+
+/// Refuse the proofs of the circuit `id` whose public input at `index`, a
+/// Unix timestamp in seconds, is older than `max_age` or ahead of the node
+/// clock by more than [`ALLOWED_SKEW`] seconds.
+///
+/// The proofs of the circuits without a maximum age are never refused for
+/// their age.
+pub fn set_max_proof_age(id: [u8; 32], index: usize, max_age: Duration) {
+    MAX_PROOF_AGES
+        .write()
+        .expect("Proof ages poisoned")
+        .insert(id, (index, max_age));
+}
+
+/// Seconds a proof timestamp may be ahead of the node clock.
+pub const ALLOWED_SKEW: u64 = 30;
+
+// Check the age of a proof against the maximum set for its circuit, if any.
+fn check_proof_age(id: &[u8; 32], pi: &[BlsScalar]) -> Result<(), Status> {
+    let (index, max_age) =
+        match MAX_PROOF_AGES.read().expect("Proof ages poisoned").get(id) {
+            Some(&policy) => policy,
+            None => return Ok(()),
+        };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| Status::internal(format!("{:?}", e)))?
+        .as_secs();
+    check_timestamp(pi, index, max_age, now)
+}
+
+// Check the age of a proof checked against `verifier_data` as the one of the
+// circuit with a maximum age whose cached verifier data it is, if any.
+fn check_proof_age_for_verifier_data(
+    verifier_data: &[u8],
+    pi: &[BlsScalar],
+) -> Result<(), Status> {
+    let ids: Vec<[u8; 32]> = MAX_PROOF_AGES
+        .read()
+        .expect("Proof ages poisoned")
+        .keys()
+        .copied()
+        .collect();
+
+    let id = ids.into_iter().find(|id| {
+        rusk_profile::keys_for(id)
+            .and_then(|keys| keys.get_verifier())
+            .map_or(false, |vd| vd.as_slice() == verifier_data)
+    });
+
+    match id {
+        Some(id) => check_proof_age(&id, pi),
+        None => Ok(()),
+    }
+}
+
+// Refuse the timestamp at `index` of the public inputs when it is older than
+// `max_age` or further ahead of `now` than the allowed skew.
+fn check_timestamp(
+    pi: &[BlsScalar],
+    index: usize,
+    max_age: Duration,
+    now: u64,
+) -> Result<(), Status> {
+    // A timestamp must fit a single limb, anything else is malformed.
+    let timestamp = pi
+        .get(index)
+        .map(BlsScalar::reduce)
+        .filter(|ts| ts.0[1..].iter().all(|&limb| limb == 0))
+        .map(|ts| ts.0[0])
+        .ok_or_else(|| {
+            Status::invalid_argument(format!(
+                "Public input {} is not a timestamp",
+                index
+            ))
+        })?;
+
+    if timestamp > now.saturating_add(ALLOWED_SKEW) {
+        return Err(Status::failed_precondition(format!(
+            "Proof of {} is more than {} seconds in the future",
+            timestamp, ALLOWED_SKEW
+        )));
+    }
+    if now.saturating_sub(timestamp) > max_age.as_secs() {
+        return Err(Status::failed_precondition(format!(
+            "Proof of {} is older than {} seconds",
+            timestamp,
+            max_age.as_secs()
+        )));
+    }
+    Ok(())
+}
+
+// Verify a proof against the given verifier data using the node's CRS, with
+// the transcript initialized by `label`.
+fn verify_with(
+    vd: &VerifierData,
+    proof: &Proof,
+    pi: &[BlsScalar],
+    label: &'static [u8],
+) -> bool {
+    let pi: Vec<PublicInputValue> =
+        pi.iter().map(|&pi| PublicInputValue::from(pi)).collect();
+
+    let verified = circuit::verify_proof(
+        &crate::PUB_PARAMS,
+        &vd.key(),
+        proof,
+        &pi,
+        &vd.pi_pos(),
+        label,
+    )
+    .is_ok();
+
+    // A proof generated with another label fails like any bad proof.
+    if !verified {
+        warn!(
+            "Proof rejected, verified with transcript label {}",
+            String::from_utf8_lossy(label)
+        );
+    }
+    verified
+}
+
+// Verify a proof and describe the public inputs it was verified with.
+fn verify_response(
+    vd: &VerifierData,
+    proof: &Proof,
+    pi: &[BlsScalar],
+    label: &'static [u8],
+) -> VerifyResponse {
+    VerifyResponse {
+        success: verify_with(vd, proof, pi, label),
+        public_inputs_count: pi.len() as u32,
+        public_inputs_hash: sponge::hash(pi).to_bytes().to_vec(),
+    }
+}
+
+fn parse_proof(bytes: &[u8]) -> Result<Proof, Status> {
+    Proof::from_slice(bytes)
+        .map_err(|e| Status::failed_precondition(format!("{:?}", e)))
+}
+
+// Public inputs are sent as a flat list of `BlsScalar`s, in the same order
+// the circuit appends them.
+fn parse_public_inputs(
+    public_inputs: &[Vec<u8>],
+) -> Result<Vec<BlsScalar>, Status> {
+    public_inputs
+        .iter()
+        .map(|pi| encoding::as_status_err(BlsScalar::from_slice(pi)))
+        .collect()
+}
+
+#[tonic::async_trait]
+impl Verifier for Rusk {
+    async fn verify(
+        &self,
+        request: Request<VerifyRequest>,
+    ) -> Result<Response<VerifyResponse>, Status> {
+        info!("Recieved Verify request");
+        let res = crate::prover_pool::spawn(move || {
+            VerifyHandler::load_request(&request).handle_request()
+        })
+        .await
+        .and_then(|res| res);
+        match res {
+            Ok(response) => {
+                info!("Verify request was successfully processed. Sending response..");
+                Ok(response)
+            }
+            Err(e) => {
+                error!("An error ocurred during the Verify request processing: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+
+    async fn verify_with_vd(
+        &self,
+        request: Request<VerifyWithVdRequest>,
+    ) -> Result<Response<VerifyResponse>, Status> {
+        info!("Recieved VerifyWithVd request");
+        let res = crate::prover_pool::spawn(move || {
+            VerifyWithVdHandler::load_request(&request).handle_request()
+        })
+        .await
+        .and_then(|res| res);
+        match res {
+            Ok(response) => {
+                info!("VerifyWithVd request was successfully processed. Sending response..");
+                Ok(response)
+            }
+            Err(e) => {
+                error!("An error ocurred during the VerifyWithVd request processing: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+
+    async fn verify_dry(
+        &self,
+        request: Request<VerifyRequest>,
+    ) -> Result<Response<VerifyDryResponse>, Status> {
+        info!("Recieved VerifyDry request");
+        let res = crate::prover_pool::spawn(move || {
+            VerifyDryHandler::load_request(&request).handle_request()
+        })
+        .await
+        .and_then(|res| res);
+        match res {
+            Ok(response) => {
+                info!("VerifyDry request was successfully processed. Sending response..");
+                Ok(response)
+            }
+            Err(e) => {
+                error!("An error ocurred during the VerifyDry request processing: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+
+    async fn verify_any(
+        &self,
+        request: Request<VerifyAnyRequest>,
+    ) -> Result<Response<VerifyAnyResponse>, Status> {
+        info!("Recieved VerifyAny request");
+        let res = crate::prover_pool::spawn(move || {
+            VerifyAnyHandler::load_request(&request).handle_request()
+        })
+        .await
+        .and_then(|res| res);
+        match res {
+            Ok(response) => {
+                info!("VerifyAny request was successfully processed. Sending response..");
+                Ok(response)
+            }
+            Err(e) => {
+                error!("An error ocurred during the VerifyAny request processing: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+
+    async fn verify_batch(
+        &self,
+        request: Request<Streaming<VerifyRequest>>,
+    ) -> Result<Response<VerifyBatchResponse>, Status> {
+        info!("Recieved VerifyBatch request");
+        let mut stream = request.into_inner();
+
+        // Accumulate the whole batch before verifying so the results keep
+        // the order in which the proofs were streamed.
+        let mut requests = vec![];
+        while let Some(request) = stream.message().await? {
+            if requests.len() == MAX_BATCH_SIZE {
+                let e = Status::invalid_argument(format!(
+                    "At most {} proofs can be verified in a batch",
+                    MAX_BATCH_SIZE
+                ));
+                warn!("An error ocurred during the VerifyBatch request processing: {:?}", e);
+                return Err(e);
+            }
+            requests.push(request);
+        }
+
+        // Verifying is CPU bound, so the batch is handed to the prover pool
+        // rather than held on the runtime.
+        let results = crate::prover_pool::spawn(move || {
+            requests
+                .iter()
+                .map(|request| {
+                    verify_handler::verify_request(request)
+                        .map(|response| response.success)
+                        .unwrap_or_else(|e| {
+                            error!(
+                                "Batch entry could not be verified: {:?}",
+                                e
+                            );
+                            false
+                        })
+                })
+                .collect::<Vec<_>>()
+        })
+        .await?;
+
+        info!("VerifyBatch request was successfully processed. Sending response..");
+        Ok(Response::new(VerifyBatchResponse { results }))
+    }
+
+    async fn supported_circuits(
+        &self,
+        request: Request<SupportedCircuitsRequest>,
+    ) -> Result<Response<SupportedCircuitsResponse>, Status> {
+        let handler = SupportedCircuitsHandler::load_request(&request);
+        info!("Recieved SupportedCircuits request");
+        match handler.handle_request() {
+            Ok(response) => {
+                info!("SupportedCircuits request was successfully processed. Sending response..");
+                Ok(response)
+            }
+            Err(e) => {
+                error!("An error ocurred during the SupportedCircuits request processing: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+
+    async fn keys_fingerprint(
+        &self,
+        request: Request<KeysFingerprintRequest>,
+    ) -> Result<Response<KeysFingerprintResponse>, Status> {
+        let handler = KeysFingerprintHandler::load_request(&request);
+        info!("Recieved KeysFingerprint request");
+        match handler.handle_request() {
+            Ok(response) => {
+                info!("KeysFingerprint request was successfully processed. Sending response..");
+                Ok(response)
+            }
+            Err(e) => {
+                error!("An error ocurred during the KeysFingerprint request processing: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::Code;
+
+    const NOW: u64 = 1_600_000_000;
+    const MAX_AGE: Duration = Duration::from_secs(60);
+
+    fn pi(timestamp: u64) -> Vec<BlsScalar> {
+        vec![BlsScalar::one(), BlsScalar::from(timestamp)]
+    }
+
+    fn code(result: Result<(), Status>) -> Option<Code> {
+        result.err().map(|status| status.code())
+    }
+
+    #[test]
+    fn timestamps_within_age_pass() {
+        assert!(check_timestamp(&pi(NOW), 1, MAX_AGE, NOW).is_ok());
+        assert!(check_timestamp(&pi(NOW - 60), 1, MAX_AGE, NOW).is_ok());
+        assert!(
+            check_timestamp(&pi(NOW + ALLOWED_SKEW), 1, MAX_AGE, NOW).is_ok()
+        );
+    }
+
+    #[test]
+    fn stale_timestamps_are_refused() {
+        assert_eq!(
+            code(check_timestamp(&pi(NOW - 61), 1, MAX_AGE, NOW)),
+            Some(Code::FailedPrecondition)
+        );
+        assert_eq!(
+            code(check_timestamp(&pi(0), 1, MAX_AGE, NOW)),
+            Some(Code::FailedPrecondition)
+        );
+    }
+
+    #[test]
+    fn future_timestamps_are_refused() {
+        assert_eq!(
+            code(check_timestamp(
+                &pi(NOW + ALLOWED_SKEW + 1),
+                1,
+                MAX_AGE,
+                NOW
+            )),
+            Some(Code::FailedPrecondition)
+        );
+        assert_eq!(
+            code(check_timestamp(&pi(u64::MAX), 1, MAX_AGE, NOW)),
+            Some(Code::FailedPrecondition)
+        );
+    }
+
+    #[test]
+    fn malformed_timestamps_are_refused() {
+        let pi = vec![BlsScalar::one(), -BlsScalar::one()];
+        assert_eq!(
+            code(check_timestamp(&pi, 1, MAX_AGE, NOW)),
+            Some(Code::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn missing_timestamps_are_refused() {
+        assert_eq!(
+            code(check_timestamp(&pi(NOW), 2, MAX_AGE, NOW)),
+            Some(Code::InvalidArgument)
+        );
+        assert_eq!(
+            code(check_timestamp(&[], 0, MAX_AGE, NOW)),
+            Some(Code::InvalidArgument)
+        );
+    }
+}
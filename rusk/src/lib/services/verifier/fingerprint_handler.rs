@@ -0,0 +1,43 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::super::ServiceRequestHandler;
+use super::{KeysFingerprintRequest, KeysFingerprintResponse};
+use crate::encoding;
+use tonic::{Request, Response, Status};
+
+/// Implementation of the KeysFingerprint Handler.
+pub struct KeysFingerprintHandler<'a> {
+    _request: &'a Request<KeysFingerprintRequest>,
+}
+
+impl<'a, 'b>
+    ServiceRequestHandler<
+        'a,
+        'b,
+        KeysFingerprintRequest,
+        KeysFingerprintResponse,
+    > for KeysFingerprintHandler<'a>
+where
+    'b: 'a,
+{
+    fn load_request(request: &'b Request<KeysFingerprintRequest>) -> Self {
+        Self { _request: request }
+    }
+
+    fn handle_request(
+        &self,
+    ) -> Result<Response<KeysFingerprintResponse>, Status> {
+        // The request carries no fields, the fingerprint only depends on
+        // the keys cached in the node's profile.
+        let fingerprint =
+            encoding::as_status_err(rusk_profile::keys_fingerprint())?;
+
+        Ok(Response::new(KeysFingerprintResponse {
+            fingerprint: fingerprint.to_vec(),
+        }))
+    }
+}
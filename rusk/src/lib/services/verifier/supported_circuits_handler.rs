@@ -0,0 +1,43 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::super::ServiceRequestHandler;
+use super::{SupportedCircuitsRequest, SupportedCircuitsResponse};
+use crate::encoding;
+use tonic::{Request, Response, Status};
+
+/// Implementation of the SupportedCircuits Handler.
+pub struct SupportedCircuitsHandler<'a> {
+    _request: &'a Request<SupportedCircuitsRequest>,
+}
+
+impl<'a, 'b>
+    ServiceRequestHandler<
+        'a,
+        'b,
+        SupportedCircuitsRequest,
+        SupportedCircuitsResponse,
+    > for SupportedCircuitsHandler<'a>
+where
+    'b: 'a,
+{
+    fn load_request(request: &'b Request<SupportedCircuitsRequest>) -> Self {
+        Self { _request: request }
+    }
+
+    fn handle_request(
+        &self,
+    ) -> Result<Response<SupportedCircuitsResponse>, Status> {
+        // A circuit is supported as long as its keys are in the cache.
+        let circuit_ids =
+            encoding::as_status_err(rusk_profile::cached_circuit_ids())?
+                .iter()
+                .map(|id| id.to_vec())
+                .collect();
+
+        Ok(Response::new(SupportedCircuitsResponse { circuit_ids }))
+    }
+}
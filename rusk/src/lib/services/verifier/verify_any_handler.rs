@@ -0,0 +1,79 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::super::ServiceRequestHandler;
+use super::{VerifyAnyRequest, VerifyAnyResponse};
+use dusk_plonk::prelude::*;
+use tonic::{Request, Response, Status};
+use tracing::warn;
+
+/// Candidates a single request can try, enough for every `Execute` shape.
+const MAX_CANDIDATES: usize = 16;
+
+/// Implementation of the VerifyAny Handler.
+pub struct VerifyAnyHandler<'a> {
+    request: &'a Request<VerifyAnyRequest>,
+}
+
+impl<'a, 'b> ServiceRequestHandler<'a, 'b, VerifyAnyRequest, VerifyAnyResponse>
+    for VerifyAnyHandler<'a>
+where
+    'b: 'a,
+{
+    fn load_request(request: &'b Request<VerifyAnyRequest>) -> Self {
+        Self { request }
+    }
+
+    fn handle_request(&self) -> Result<Response<VerifyAnyResponse>, Status> {
+        let request = self.request.get_ref();
+
+        if request.candidate_ids.len() > MAX_CANDIDATES {
+            return Err(Status::invalid_argument(format!(
+                "At most {} candidate circuits can be tried",
+                MAX_CANDIDATES
+            )));
+        }
+        let candidates = request
+            .candidate_ids
+            .iter()
+            .map(|id| super::verify_handler::parse_circuit_id(id))
+            .collect::<Result<Vec<_>, _>>()?;
+        let proof = super::parse_proof(&request.proof)?;
+        let pi = super::parse_public_inputs(&request.public_inputs)?;
+
+        let verified = candidates.into_iter().find(|id| {
+            verify_candidate(id, &proof, &pi)
+                .map_err(|e| {
+                    warn!("Candidate {} skipped: {:?}", hex::encode(id), e)
+                })
+                .unwrap_or(false)
+        });
+
+        Ok(Response::new(VerifyAnyResponse {
+            circuit_id: verified.map(|id| id.to_vec()).unwrap_or_default(),
+        }))
+    }
+}
+
+// Verify the proof for a single candidate, failing when the node can't, e.g.
+// because it lacks the keys of the circuit.
+fn verify_candidate(
+    id: &[u8; 32],
+    proof: &Proof,
+    pi: &[BlsScalar],
+) -> Result<bool, Status> {
+    let keys = crate::circuits::cached_keys(id)?;
+    let vd = crate::encoding::as_status_err(keys.get_verifier())?;
+    let vd = crate::encoding::as_status_err(VerifierData::from_slice(&vd))?;
+    super::check_proof_age(id, pi)?;
+
+    Ok(super::verify_with(
+        &vd,
+        proof,
+        pi,
+        crate::circuits::transcript_label(id),
+    ))
+}
@@ -0,0 +1,49 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::super::ServiceRequestHandler;
+use super::{VerifyDryResponse, VerifyRequest};
+use crate::encoding;
+use dusk_plonk::prelude::*;
+use tonic::{Request, Response, Status};
+
+/// Implementation of the VerifyDry Handler.
+pub struct VerifyDryHandler<'a> {
+    request: &'a Request<VerifyRequest>,
+}
+
+impl<'a, 'b> ServiceRequestHandler<'a, 'b, VerifyRequest, VerifyDryResponse>
+    for VerifyDryHandler<'a>
+where
+    'b: 'a,
+{
+    fn load_request(request: &'b Request<VerifyRequest>) -> Self {
+        Self { request }
+    }
+
+    fn handle_request(&self) -> Result<Response<VerifyDryResponse>, Status> {
+        let request = self.request.get_ref();
+
+        // Only the verifier data is read, the CRS is left untouched.
+        let circuit_id =
+            super::verify_handler::parse_circuit_id(&request.circuit_id)?;
+        let keys = crate::circuits::cached_keys(&circuit_id)?;
+        let vd = encoding::as_status_err(keys.get_verifier())?;
+        let vd = encoding::as_status_err(VerifierData::from_slice(&vd))?;
+
+        let expected = vd.pi_pos().len();
+        let count = request.public_inputs.len();
+        let parses = super::parse_proof(&request.proof).is_ok()
+            && super::parse_public_inputs(&request.public_inputs).is_ok()
+            && count == expected;
+
+        Ok(Response::new(VerifyDryResponse {
+            parses,
+            public_inputs_count: count as u32,
+            expected_public_inputs_count: expected as u32,
+        }))
+    }
+}
@@ -0,0 +1,68 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::super::ServiceRequestHandler;
+use super::{VerifyRequest, VerifyResponse};
+use crate::encoding;
+use dusk_plonk::prelude::*;
+use tonic::{Request, Response, Status};
+
+/// Implementation of the Verify Handler.
+pub struct VerifyHandler<'a> {
+    request: &'a Request<VerifyRequest>,
+}
+
+impl<'a, 'b> ServiceRequestHandler<'a, 'b, VerifyRequest, VerifyResponse>
+    for VerifyHandler<'a>
+where
+    'b: 'a,
+{
+    fn load_request(request: &'b Request<VerifyRequest>) -> Self {
+        Self { request }
+    }
+
+    fn handle_request(&self) -> Result<Response<VerifyResponse>, Status> {
+        Ok(Response::new(verify_request(self.request.get_ref())?))
+    }
+}
+
+/// Verify the proof contained in a [`VerifyRequest`] against the keys
+/// cached for its circuit.
+///
+/// An error is returned when the request is malformed or the keys of the
+/// circuit are not cached. A proof that does not verify is reported through
+/// the `success` field of the response.
+pub(crate) fn verify_request(
+    request: &VerifyRequest,
+) -> Result<VerifyResponse, Status> {
+    let circuit_id = parse_circuit_id(&request.circuit_id)?;
+    let proof = super::parse_proof(&request.proof)?;
+    let pi = super::parse_public_inputs(&request.public_inputs)?;
+    super::check_proof_age(&circuit_id, &pi)?;
+
+    let keys = crate::circuits::cached_keys(&circuit_id)?;
+    let vd = encoding::as_status_err(keys.get_verifier())?;
+    let vd = encoding::as_status_err(VerifierData::from_slice(&vd))?;
+
+    Ok(super::verify_response(
+        &vd,
+        &proof,
+        &pi,
+        crate::circuits::transcript_label(&circuit_id),
+    ))
+}
+
+pub(super) fn parse_circuit_id(bytes: &[u8]) -> Result<[u8; 32], Status> {
+    if bytes.len() != 32 {
+        return Err(Status::invalid_argument(
+            "The circuit id must be 32 bytes long",
+        ));
+    }
+
+    let mut circuit_id = [0u8; 32];
+    circuit_id.copy_from_slice(bytes);
+    Ok(circuit_id)
+}
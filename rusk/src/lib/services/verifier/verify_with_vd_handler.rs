@@ -0,0 +1,47 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::super::ServiceRequestHandler;
+use super::{VerifyResponse, VerifyWithVdRequest};
+use dusk_plonk::prelude::*;
+use tonic::{Request, Response, Status};
+
+/// Implementation of the VerifyWithVd Handler.
+pub struct VerifyWithVdHandler<'a> {
+    request: &'a Request<VerifyWithVdRequest>,
+}
+
+impl<'a, 'b> ServiceRequestHandler<'a, 'b, VerifyWithVdRequest, VerifyResponse>
+    for VerifyWithVdHandler<'a>
+where
+    'b: 'a,
+{
+    fn load_request(request: &'b Request<VerifyWithVdRequest>) -> Self {
+        Self { request }
+    }
+
+    fn handle_request(&self) -> Result<Response<VerifyResponse>, Status> {
+        let request = self.request.get_ref();
+
+        // The verifier data comes from the caller, only the CRS is local.
+        // Without a circuit id, the transcript label is the one of the cached
+        // circuit with the same verifier data, if any, or the shared one.
+        let vd = VerifierData::from_slice(&request.verifier_data)
+            .map_err(|e| Status::invalid_argument(format!("{:?}", e)))?;
+        let proof = super::parse_proof(&request.proof)?;
+        let pi = super::parse_public_inputs(&request.public_inputs)?;
+        super::check_proof_age_for_verifier_data(&request.verifier_data, &pi)?;
+
+        Ok(Response::new(super::verify_response(
+            &vd,
+            &proof,
+            &pi,
+            crate::circuits::transcript_label_for_verifier_data(
+                &request.verifier_data,
+            ),
+        )))
+    }
+}
@@ -9,10 +9,19 @@ pub mod encoding;
 pub mod unix;
 
 use super::SOCKET_PATH;
+use dusk_bytes::Serializable;
+use dusk_plonk::jubjub::{GENERATOR_EXTENDED, GENERATOR_NUMS_EXTENDED};
+use dusk_plonk::prelude::*;
 use futures::TryFutureExt;
+use rusk::services::admin::AdminServer;
 use rusk::services::blindbid::BlindBidServiceServer;
 use rusk::services::echoer::EchoerServer;
 use rusk::services::pki::KeysServer;
+use rusk::services::prover::{
+    ProveBidRequest, ProveResponse, ProverClient, ProverServer,
+};
+use rusk::services::proving_key::ProvingKeyServiceServer;
+use rusk::services::verifier::VerifierServer;
 use rusk::Rusk;
 use std::convert::TryFrom;
 use test_context::AsyncTestContext;
@@ -23,6 +32,34 @@ use tower::service_fn;
 use tracing::{subscriber, Level};
 use tracing_subscriber::fmt::Subscriber;
 
+/// Token the testing server expects from `ProvingKeyService` clients.
+pub const PROVING_KEY_TOKEN: &str = "rusk-test-token";
+
+/// Prove the correctness of a bid committing to a fixed value, returning
+/// the response along with the commitment.
+pub async fn prove_bid_fixture(
+    prover: &mut ProverClient<Channel>,
+) -> (ProveResponse, JubJubAffine) {
+    let value = JubJubScalar::from(100000_u64);
+    let blinder = JubJubScalar::from(50000_u64);
+    let commitment = JubJubAffine::from(
+        (GENERATOR_EXTENDED * value) + (GENERATOR_NUMS_EXTENDED * blinder),
+    );
+
+    let request = tonic::Request::new(ProveBidRequest {
+        commitment: commitment.to_bytes().to_vec(),
+        value: value.to_bytes().to_vec(),
+        blinder: blinder.to_bytes().to_vec(),
+    });
+    let response = prover
+        .prove_bid(request)
+        .await
+        .expect("Failed to prove the bid")
+        .into_inner();
+
+    (response, commitment)
+}
+
 pub struct TestContext {
     pub channel: Channel,
 }
@@ -63,6 +100,15 @@ impl AsyncTestContext for TestContext {
                 .add_service(BlindBidServiceServer::new(rusk))
                 .add_service(KeysServer::new(rusk))
                 .add_service(EchoerServer::new(rusk))
+                .add_service(VerifierServer::new(rusk))
+                .add_service(ProverServer::new(rusk))
+                .add_service(AdminServer::new(rusk))
+                .add_service(ProvingKeyServiceServer::with_interceptor(
+                    rusk,
+                    rusk::auth::token_interceptor(
+                        PROVING_KEY_TOKEN.to_string(),
+                    ),
+                ))
                 .serve_with_incoming(incoming)
                 .await
                 .unwrap();
@@ -0,0 +1,23 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::TestContext;
+use rusk::services::admin::{AdminClient, VerifyCrsRequest};
+use test_context::test_context;
+
+#[test_context(TestContext)]
+#[tokio::test]
+pub async fn verify_crs_matches_profile(
+    ctx: &mut TestContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut client = AdminClient::new(ctx.channel.clone());
+
+    let request = tonic::Request::new(VerifyCrsRequest {});
+    let response = client.verify_crs(request).await?.into_inner();
+
+    assert_eq!(response.valid, rusk::verify_crs()?);
+    Ok(())
+}
@@ -4,8 +4,12 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+pub mod admin_service;
 //pub mod blindbid_service;
 pub mod echo_service;
 pub mod pki_service;
+pub mod prover_service;
+pub mod proving_key_service;
+pub mod verifier_service;
 
 pub use super::TestContext;
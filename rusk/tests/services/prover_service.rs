@@ -0,0 +1,421 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::TestContext;
+use crate::common::prove_bid_fixture;
+use bid_circuits::BidCorrectnessCircuit;
+use blindbid_circuits::BlindBidCircuit;
+use canonical_derive::Canon;
+use dusk_blindbid::Bid;
+use dusk_bytes::{DeserializableSlice, Serializable};
+use dusk_pki::SecretSpendKey;
+use dusk_plonk::jubjub::GENERATOR_EXTENDED;
+use dusk_plonk::prelude::*;
+use dusk_poseidon::tree::{PoseidonAnnotation, PoseidonLeaf, PoseidonTree};
+use phoenix_core::{Message, Note};
+use rusk::services::prover::{
+    ProveBidRequest, ProveStcoRequest, ProveStctRequest, ProveWfoRequest,
+    ProverClient, StakeProofInputsRequest,
+};
+use std::convert::TryInto;
+use test_context::test_context;
+use transfer_circuits::{
+    SendToContractObfuscatedCircuit, SendToContractTransparentCircuit,
+};
+
+#[test_context(TestContext)]
+#[tokio::test]
+pub async fn prove_bid_verifies_with_cached_keys(
+    ctx: &mut TestContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut client = ProverClient::new(ctx.channel.clone());
+
+    let (response, commitment) = prove_bid_fixture(&mut client).await;
+    let proof = Proof::from_slice(&response.proof)?;
+
+    // The public inputs are the commitment coordinates.
+    let pi = response
+        .public_inputs
+        .iter()
+        .map(|pi| BlsScalar::from_slice(pi))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(pi, vec![commitment.get_x(), commitment.get_y()]);
+
+    let pub_params = unsafe {
+        PublicParameters::from_slice_unchecked(
+            &rusk_profile::get_common_reference_string()?,
+        )
+    };
+    let vd = VerifierData::from_slice(
+        &rusk_profile::keys_for(&BidCorrectnessCircuit::CIRCUIT_ID)?
+            .get_verifier()?,
+    )?;
+    let pi_values: Vec<PublicInputValue> =
+        pi.into_iter().map(PublicInputValue::from).collect();
+    let verify = |label: &'static [u8]| {
+        circuit::verify_proof(
+            &pub_params,
+            vd.key(),
+            &proof,
+            &pi_values,
+            vd.pi_pos(),
+            label,
+        )
+    };
+    let label =
+        rusk::circuits::transcript_label(&BidCorrectnessCircuit::CIRCUIT_ID);
+    assert!(verify(label).is_ok());
+
+    // The proof doesn't verify under another label, such as the one of a
+    // circuit whose loader gives it a label of its own.
+    let other: &'static [u8] =
+        Box::leak([label, &b"-BlindBid"[..]].concat().into_boxed_slice());
+    assert!(verify(other).is_err());
+
+    // A commitment that doesn't open to the value is refused.
+    let value = JubJubScalar::from(100000_u64);
+    let blinder = JubJubScalar::from(50000_u64);
+    let request = tonic::Request::new(ProveBidRequest {
+        commitment: JubJubAffine::from(GENERATOR_EXTENDED * value)
+            .to_bytes()
+            .to_vec(),
+        value: value.to_bytes().to_vec(),
+        blinder: blinder.to_bytes().to_vec(),
+    });
+    assert!(client.prove_bid(request).await.is_err());
+    Ok(())
+}
+
+#[derive(Debug, Clone, Canon)]
+struct BidLeaf(Bid);
+
+impl PoseidonLeaf for BidLeaf {
+    fn poseidon_hash(&self) -> BlsScalar {
+        self.0.hash()
+    }
+
+    fn pos(&self) -> &u64 {
+        self.0.pos()
+    }
+
+    fn set_pos(&mut self, pos: u64) {
+        self.0.set_pos(pos)
+    }
+}
+
+#[test]
+fn prove_blindbid_verifies_with_the_blindbid_keys(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let rng = &mut rand::thread_rng();
+
+    let secret = JubJubScalar::random(rng);
+    let secret_k = BlsScalar::random(rng);
+    let stealth_address = SecretSpendKey::random(rng)
+        .public_spend_key()
+        .gen_stealth_address(&secret);
+    let secret = JubJubAffine::from(GENERATOR_EXTENDED * secret);
+
+    // Neither expired nor waiting to be eligible.
+    let bid = Bid::new(
+        rng,
+        &stealth_address,
+        &JubJubScalar::from(60_000_u64),
+        &secret,
+        secret_k,
+        u64::MAX,
+        u64::MAX,
+    )
+    .expect("Failed to create the Bid!");
+
+    let mut tree = PoseidonTree::<BidLeaf, PoseidonAnnotation, 17>::new();
+    let pos = tree.push(BidLeaf(bid)).expect("Failed to append the Bid!");
+    let branch = tree
+        .branch(pos)
+        .expect("Failed to open the Bid tree!")
+        .expect("The Bid isn't in the tree!");
+
+    let seed = BlsScalar::random(rng);
+    let (round, step) = (50, 50);
+    let (proof, pi) = rusk::services::prover::prove_blindbid(
+        bid, secret, secret_k, &branch, seed, round, step,
+    )?;
+
+    let pub_params = unsafe {
+        PublicParameters::from_slice_unchecked(
+            &rusk_profile::get_common_reference_string()?,
+        )
+    };
+    let vd = VerifierData::from_slice(
+        &rusk_profile::keys_for(&BlindBidCircuit::CIRCUIT_ID)?
+            .get_verifier()?,
+    )?;
+    let verify = |pi: &[BlsScalar]| {
+        let pi: Vec<PublicInputValue> =
+            pi.iter().copied().map(PublicInputValue::from).collect();
+        circuit::verify_proof(
+            &pub_params,
+            vd.key(),
+            &proof,
+            &pi,
+            vd.pi_pos(),
+            rusk::circuits::transcript_label(&BlindBidCircuit::CIRCUIT_ID),
+        )
+    };
+    assert!(verify(&pi).is_ok());
+
+    // The proof doesn't hold for any other public input, such as the root
+    // of another Bid tree.
+    let mut other = pi.clone();
+    other[0] = BlsScalar::random(rng);
+    assert!(verify(&other).is_err());
+    Ok(())
+}
+
+#[test_context(TestContext)]
+#[tokio::test]
+pub async fn prove_stct_requires_crossover_owner(
+    ctx: &mut TestContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut client = ProverClient::new(ctx.channel.clone());
+    let rng = &mut rand::thread_rng();
+
+    let ssk = SecretSpendKey::random(rng);
+    let address = BlsScalar::random(rng);
+    let value = 100;
+
+    let note = Note::obfuscated(
+        rng,
+        &ssk.public_spend_key(),
+        value,
+        JubJubScalar::random(rng),
+    );
+    let (mut fee, crossover) = note
+        .try_into()
+        .expect("Failed to convert note into fee/crossover pair!");
+    fee.gas_limit = 5;
+    fee.gas_price = 1;
+
+    let signature = SendToContractTransparentCircuit::sign(
+        rng, &ssk, &fee, &crossover, value, &address,
+    );
+
+    let request = |vk: &[u8]| {
+        tonic::Request::new(ProveStctRequest {
+            fee: fee.to_bytes().to_vec(),
+            crossover: crossover.to_bytes().to_vec(),
+            view_key: vk.to_vec(),
+            address: address.to_bytes().to_vec(),
+            signature: signature.to_bytes().to_vec(),
+        })
+    };
+
+    let response = client
+        .prove_stct(request(&ssk.view_key().to_bytes()))
+        .await?
+        .into_inner();
+    Proof::from_slice(&response.proof)?;
+
+    // Someone else's view key can't decrypt the crossover.
+    let other = SecretSpendKey::random(rng).view_key();
+    assert!(client.prove_stct(request(&other.to_bytes())).await.is_err());
+    Ok(())
+}
+
+#[test_context(TestContext)]
+#[tokio::test]
+pub async fn stake_proof_inputs_match_the_stct_proof(
+    ctx: &mut TestContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut client = ProverClient::new(ctx.channel.clone());
+    let rng = &mut rand::thread_rng();
+
+    let ssk = SecretSpendKey::random(rng);
+    let caller = BlsScalar::random(rng);
+    let value = 100;
+
+    let note = Note::obfuscated(
+        rng,
+        &ssk.public_spend_key(),
+        value,
+        JubJubScalar::random(rng),
+    );
+    let (mut fee, crossover) = note
+        .try_into()
+        .expect("Failed to convert note into fee/crossover pair!");
+    fee.gas_limit = 5;
+    fee.gas_price = 1;
+
+    let signature = SendToContractTransparentCircuit::sign(
+        rng, &ssk, &fee, &crossover, value, &caller,
+    );
+
+    let proved = client
+        .prove_stct(tonic::Request::new(ProveStctRequest {
+            fee: fee.to_bytes().to_vec(),
+            crossover: crossover.to_bytes().to_vec(),
+            view_key: ssk.view_key().to_bytes().to_vec(),
+            address: caller.to_bytes().to_vec(),
+            signature: signature.to_bytes().to_vec(),
+        }))
+        .await?
+        .into_inner();
+
+    let request = |value: u64| {
+        tonic::Request::new(StakeProofInputsRequest {
+            fee: fee.to_bytes().to_vec(),
+            crossover: crossover.to_bytes().to_vec(),
+            value,
+            caller: caller.to_bytes().to_vec(),
+        })
+    };
+
+    let inputs = client
+        .stake_proof_inputs(request(value))
+        .await?
+        .into_inner();
+    assert_eq!(inputs.public_inputs, proved.public_inputs);
+
+    // Staking another value than the crossover one can't be proved.
+    let inputs = client
+        .stake_proof_inputs(request(value + 1))
+        .await?
+        .into_inner();
+    assert_ne!(inputs.public_inputs, proved.public_inputs);
+
+    // A malformed caller is refused.
+    let mut malformed = request(value);
+    malformed.get_mut().caller = vec![0xff; 32];
+    assert!(client.stake_proof_inputs(malformed).await.is_err());
+    Ok(())
+}
+
+#[test_context(TestContext)]
+#[tokio::test]
+pub async fn prove_stco_verifies_with_the_stco_keys(
+    ctx: &mut TestContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut client = ProverClient::new(ctx.channel.clone());
+    let rng = &mut rand::thread_rng();
+
+    let ssk = SecretSpendKey::random(rng);
+    let psk = ssk.public_spend_key();
+    let address = BlsScalar::random(rng);
+
+    let note = Note::obfuscated(rng, &psk, 100, JubJubScalar::random(rng));
+    let (mut fee, crossover) = note
+        .try_into()
+        .expect("Failed to convert note into fee/crossover pair!");
+    fee.gas_limit = 5;
+    fee.gas_price = 1;
+
+    let pub_params = unsafe {
+        PublicParameters::from_slice_unchecked(
+            &rusk_profile::get_common_reference_string()?,
+        )
+    };
+    let vd = VerifierData::from_slice(
+        &rusk_profile::keys_for(&SendToContractObfuscatedCircuit::CIRCUIT_ID)?
+            .get_verifier()?,
+    )?;
+    let label = rusk::circuits::transcript_label(
+        &SendToContractObfuscatedCircuit::CIRCUIT_ID,
+    );
+
+    // The crossover value commitment only opens to the note value.
+    for &(value, valid) in [(100, true), (99, false)].iter() {
+        let message_r = JubJubScalar::random(rng);
+        let message = Message::new(rng, &message_r, &psk, value);
+        let signature = SendToContractObfuscatedCircuit::sign(
+            rng, &ssk, &fee, &crossover, &message, &address,
+        );
+
+        let request = tonic::Request::new(ProveStcoRequest {
+            fee: fee.to_bytes().to_vec(),
+            crossover: crossover.to_bytes().to_vec(),
+            view_key: ssk.view_key().to_bytes().to_vec(),
+            signature: signature.to_bytes().to_vec(),
+            public_message_pk: true,
+            message: message.to_bytes().to_vec(),
+            message_psk: psk.to_bytes().to_vec(),
+            message_r: message_r.to_bytes().to_vec(),
+            address: address.to_bytes().to_vec(),
+        });
+
+        // A wrong value is either refused by the prover or proved with a
+        // proof that doesn't verify.
+        let response = match client.prove_stco(request).await {
+            Ok(response) => response.into_inner(),
+            Err(_) if !valid => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let proof = Proof::from_slice(&response.proof)?;
+        let pi = response
+            .public_inputs
+            .iter()
+            .map(|pi| BlsScalar::from_slice(pi).map(PublicInputValue::from))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let verified = circuit::verify_proof(
+            &pub_params,
+            vd.key(),
+            &proof,
+            &pi,
+            vd.pi_pos(),
+            label,
+        );
+        assert_eq!(verified.is_ok(), valid);
+    }
+    Ok(())
+}
+
+#[test_context(TestContext)]
+#[tokio::test]
+pub async fn prove_wfo_requires_balanced_values(
+    ctx: &mut TestContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut client = ProverClient::new(ctx.channel.clone());
+    let rng = &mut rand::thread_rng();
+
+    let i_ssk = SecretSpendKey::random(rng);
+    let i_note = Note::obfuscated(
+        rng,
+        &i_ssk.public_spend_key(),
+        100,
+        JubJubScalar::random(rng),
+    );
+
+    let c_psk = SecretSpendKey::random(rng).public_spend_key();
+    let c_r = JubJubScalar::random(rng);
+    let c = Message::new(rng, &c_r, &c_psk, 25);
+
+    let o_ssk = SecretSpendKey::random(rng);
+    let mut output = |value| {
+        Note::obfuscated(
+            rng,
+            &o_ssk.public_spend_key(),
+            value,
+            JubJubScalar::random(rng),
+        )
+    };
+    let request = |o_note: &Note| {
+        tonic::Request::new(ProveWfoRequest {
+            input: i_note.to_bytes().to_vec(),
+            input_view_key: i_ssk.view_key().to_bytes().to_vec(),
+            change: c.to_bytes().to_vec(),
+            change_r: c_r.to_bytes().to_vec(),
+            change_psk: c_psk.to_bytes().to_vec(),
+            output: o_note.to_bytes().to_vec(),
+            output_view_key: o_ssk.view_key().to_bytes().to_vec(),
+        })
+    };
+
+    let response = client.prove_wfo(request(&output(75))).await?.into_inner();
+    Proof::from_slice(&response.proof)?;
+
+    // The input can't be withdrawn into less than its value.
+    assert!(client.prove_wfo(request(&output(70))).await.is_err());
+    Ok(())
+}
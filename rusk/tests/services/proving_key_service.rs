@@ -0,0 +1,40 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::TestContext;
+use crate::common::PROVING_KEY_TOKEN;
+use blindbid_circuits::BlindBidCircuit;
+use rusk::auth::AUTHORIZATION;
+use rusk::services::proving_key::{ProvingKeyRequest, ProvingKeyServiceClient};
+use test_context::test_context;
+
+#[test_context(TestContext)]
+#[tokio::test]
+pub async fn proving_key_requires_token(
+    ctx: &mut TestContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut client = ProvingKeyServiceClient::new(ctx.channel.clone());
+
+    let request = tonic::Request::new(ProvingKeyRequest {
+        circuit_id: BlindBidCircuit::CIRCUIT_ID.to_vec(),
+    });
+    let status = client.get_proving_key(request).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+    let mut request = tonic::Request::new(ProvingKeyRequest {
+        circuit_id: BlindBidCircuit::CIRCUIT_ID.to_vec(),
+    });
+    request
+        .metadata_mut()
+        .insert(AUTHORIZATION, PROVING_KEY_TOKEN.parse()?);
+    let response = client.get_proving_key(request).await?.into_inner();
+
+    assert_eq!(
+        Some(response.proving_key),
+        rusk_profile::proving_key_for(&BlindBidCircuit::CIRCUIT_ID)
+    );
+    Ok(())
+}
@@ -0,0 +1,280 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::TestContext;
+use crate::common::prove_bid_fixture;
+use bid_circuits::BidCorrectnessCircuit;
+use blindbid_circuits::BlindBidCircuit;
+use dusk_bytes::Serializable;
+use dusk_plonk::prelude::*;
+use dusk_poseidon::sponge;
+use rusk::services::prover::ProverClient;
+use rusk::services::verifier::{
+    KeysFingerprintRequest, SupportedCircuitsRequest, VerifierClient,
+    VerifyAnyRequest, VerifyRequest, VerifyWithVdRequest,
+};
+use test_context::test_context;
+
+#[test_context(TestContext)]
+#[tokio::test]
+pub async fn keys_fingerprint_matches_profile(
+    ctx: &mut TestContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut client = VerifierClient::new(ctx.channel.clone());
+
+    let request = tonic::Request::new(KeysFingerprintRequest {});
+    let response = client.keys_fingerprint(request).await?.into_inner();
+
+    assert_eq!(
+        response.fingerprint,
+        rusk_profile::keys_fingerprint()?.to_vec()
+    );
+    Ok(())
+}
+
+#[test_context(TestContext)]
+#[tokio::test]
+pub async fn verify_rejects_malformed_circuit_id(
+    ctx: &mut TestContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut client = VerifierClient::new(ctx.channel.clone());
+
+    let request = tonic::Request::new(VerifyRequest {
+        circuit_id: vec![0u8; 31],
+        proof: vec![],
+        public_inputs: vec![],
+    });
+    let status = client.verify(request).await.unwrap_err();
+
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    Ok(())
+}
+
+#[test_context(TestContext)]
+#[tokio::test]
+pub async fn verify_batch_keeps_one_result_per_request(
+    ctx: &mut TestContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut client = VerifierClient::new(ctx.channel.clone());
+
+    let requests: Vec<_> = (0..3)
+        .map(|_| VerifyRequest {
+            circuit_id: vec![0u8; 32],
+            proof: vec![],
+            public_inputs: vec![],
+        })
+        .collect();
+    let response = client
+        .verify_batch(tonic::Request::new(futures::stream::iter(requests)))
+        .await?
+        .into_inner();
+
+    assert_eq!(response.results, vec![false; 3]);
+    Ok(())
+}
+
+#[test_context(TestContext)]
+#[tokio::test]
+pub async fn verify_batch_answers_in_request_order(
+    ctx: &mut TestContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut prover = ProverClient::new(ctx.channel.clone());
+    let mut client = VerifierClient::new(ctx.channel.clone());
+
+    let (response, commitment) = prove_bid_fixture(&mut prover).await;
+    let pi = [commitment.get_x(), commitment.get_y()];
+    let valid = VerifyRequest {
+        circuit_id: BidCorrectnessCircuit::CIRCUIT_ID.to_vec(),
+        proof: response.proof.clone(),
+        public_inputs: pi.iter().map(|pi| pi.to_bytes().to_vec()).collect(),
+    };
+
+    // A truncated proof, a malformed public input and an unknown circuit.
+    let truncated = VerifyRequest {
+        proof: response.proof[..response.proof.len() / 2].to_vec(),
+        ..valid.clone()
+    };
+    let malformed = VerifyRequest {
+        public_inputs: vec![vec![0xff; 32], vec![]],
+        ..valid.clone()
+    };
+    let unknown = VerifyRequest {
+        circuit_id: vec![0u8; 32],
+        ..valid.clone()
+    };
+
+    let requests = vec![truncated, valid.clone(), malformed, unknown, valid];
+    let response = client
+        .verify_batch(tonic::Request::new(futures::stream::iter(requests)))
+        .await?
+        .into_inner();
+
+    assert_eq!(response.results, vec![false, true, false, false, true]);
+    Ok(())
+}
+
+#[test_context(TestContext)]
+#[tokio::test]
+pub async fn verify_batch_refuses_oversized_batches(
+    ctx: &mut TestContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut client = VerifierClient::new(ctx.channel.clone());
+
+    let requests: Vec<_> = (0..257)
+        .map(|_| VerifyRequest {
+            circuit_id: vec![0u8; 32],
+            proof: vec![],
+            public_inputs: vec![],
+        })
+        .collect();
+    let status = client
+        .verify_batch(tonic::Request::new(futures::stream::iter(requests)))
+        .await
+        .unwrap_err();
+
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    Ok(())
+}
+
+#[test_context(TestContext)]
+#[tokio::test]
+pub async fn supported_circuits_lists_cached_keys(
+    ctx: &mut TestContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut client = VerifierClient::new(ctx.channel.clone());
+
+    let request = tonic::Request::new(SupportedCircuitsRequest {});
+    let response = client.supported_circuits(request).await?.into_inner();
+
+    // The build script always caches the BlindBid keys.
+    assert!(response
+        .circuit_ids
+        .contains(&BlindBidCircuit::CIRCUIT_ID.to_vec()));
+    Ok(())
+}
+
+#[test_context(TestContext)]
+#[tokio::test]
+pub async fn verify_reports_public_inputs_used(
+    ctx: &mut TestContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut prover = ProverClient::new(ctx.channel.clone());
+    let mut client = VerifierClient::new(ctx.channel.clone());
+
+    let (response, commitment) = prove_bid_fixture(&mut prover).await;
+    let proof = response.proof;
+
+    // The commitment is appended to the circuit as its two coordinates.
+    let pi = [commitment.get_x(), commitment.get_y()];
+    let request = tonic::Request::new(VerifyRequest {
+        circuit_id: BidCorrectnessCircuit::CIRCUIT_ID.to_vec(),
+        proof,
+        public_inputs: pi.iter().map(|pi| pi.to_bytes().to_vec()).collect(),
+    });
+    let response = client.verify(request).await?.into_inner();
+
+    assert!(response.success);
+    assert_eq!(response.public_inputs_count, 2);
+    assert_eq!(
+        response.public_inputs_hash,
+        sponge::hash(&pi).to_bytes().to_vec()
+    );
+    Ok(())
+}
+
+#[test_context(TestContext)]
+#[tokio::test]
+pub async fn verify_dry_checks_public_inputs_count(
+    ctx: &mut TestContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut prover = ProverClient::new(ctx.channel.clone());
+    let mut client = VerifierClient::new(ctx.channel.clone());
+
+    let (response, commitment) = prove_bid_fixture(&mut prover).await;
+    let proof = response.proof;
+
+    let request = |pi: &[BlsScalar]| {
+        tonic::Request::new(VerifyRequest {
+            circuit_id: BidCorrectnessCircuit::CIRCUIT_ID.to_vec(),
+            proof: proof.clone(),
+            public_inputs: pi.iter().map(|pi| pi.to_bytes().to_vec()).collect(),
+        })
+    };
+
+    let pi = [commitment.get_x(), commitment.get_y()];
+    let response = client.verify_dry(request(&pi)).await?.into_inner();
+    assert!(response.parses);
+    assert_eq!(response.expected_public_inputs_count, 2);
+
+    // A missing coordinate is caught without verifying the proof.
+    let response = client.verify_dry(request(&pi[..1])).await?.into_inner();
+    assert!(!response.parses);
+    assert_eq!(response.public_inputs_count, 1);
+    Ok(())
+}
+
+#[test_context(TestContext)]
+#[tokio::test]
+pub async fn verify_any_finds_the_proving_circuit(
+    ctx: &mut TestContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut prover = ProverClient::new(ctx.channel.clone());
+    let mut client = VerifierClient::new(ctx.channel.clone());
+
+    let (response, _) = prove_bid_fixture(&mut prover).await;
+
+    let request = |candidates: &[[u8; 32]]| {
+        tonic::Request::new(VerifyAnyRequest {
+            proof: response.proof.clone(),
+            public_inputs: response.public_inputs.clone(),
+            candidate_ids: candidates.iter().map(|id| id.to_vec()).collect(),
+        })
+    };
+
+    let candidates = [
+        BlindBidCircuit::CIRCUIT_ID,
+        BidCorrectnessCircuit::CIRCUIT_ID,
+    ];
+    let found = client.verify_any(request(&candidates)).await?.into_inner();
+    assert_eq!(found.circuit_id, BidCorrectnessCircuit::CIRCUIT_ID.to_vec());
+
+    let found = client
+        .verify_any(request(&candidates[..1]))
+        .await?
+        .into_inner();
+    assert!(found.circuit_id.is_empty());
+    Ok(())
+}
+
+#[test_context(TestContext)]
+#[tokio::test]
+pub async fn verify_with_vd_agrees_with_verify(
+    ctx: &mut TestContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut prover = ProverClient::new(ctx.channel.clone());
+    let mut client = VerifierClient::new(ctx.channel.clone());
+
+    let (response, _) = prove_bid_fixture(&mut prover).await;
+    let verifier_data =
+        rusk_profile::keys_for(&BidCorrectnessCircuit::CIRCUIT_ID)?
+            .get_verifier()?;
+
+    let request = tonic::Request::new(VerifyRequest {
+        circuit_id: BidCorrectnessCircuit::CIRCUIT_ID.to_vec(),
+        proof: response.proof.clone(),
+        public_inputs: response.public_inputs.clone(),
+    });
+    assert!(client.verify(request).await?.into_inner().success);
+
+    // The same proof verifies against the verifier data the node caches.
+    let request = tonic::Request::new(VerifyWithVdRequest {
+        verifier_data,
+        proof: response.proof,
+        public_inputs: response.public_inputs,
+    });
+    assert!(client.verify_with_vd(request).await?.into_inner().success);
+    Ok(())
+}